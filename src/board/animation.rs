@@ -0,0 +1,139 @@
+use super::*;
+use crate::chess_core::{BoardChange, Color, Piece, Position};
+
+/// How long a piece takes to slide or fade.
+const DURATION: &str = "0.2s";
+
+/// How a piece enters its square after the board changes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Animation {
+    /// The piece was already on the square.
+    Still,
+    /// The piece slides in from the square.
+    Slide(Square),
+    /// The piece appears, like the queen of a promotion.
+    FadeIn,
+    /// The piece left the board, like a captured piece. It is
+    /// drawn from the old position until it has faded.
+    FadeOut,
+}
+
+impl Animation {
+    /// The inline style that plays the animation on a piece at the square.
+    pub fn style(self, square: Square, flipped: bool) -> String {
+        match self {
+            Self::Still => String::new(),
+            Self::Slide(from) => {
+                let (fx, fy) = square_center(from, flipped);
+                let (tx, ty) = square_center(square, flipped);
+                format!(
+                    "--dx: {}px; --dy: {}px; animation: slide {DURATION} ease-out;",
+                    fx as i32 - tx as i32,
+                    fy as i32 - ty as i32,
+                )
+            }
+            Self::FadeIn => format!("animation: fade-in {DURATION} ease-out;"),
+            Self::FadeOut => format!("animation: fade-out {DURATION} ease-out forwards;"),
+        }
+    }
+}
+
+/// The keyframes referenced by 'Animation::style'.
+pub const KEYFRAMES: &str = "
+@keyframes slide { from { transform: translate(var(--dx), var(--dy)); } }
+@keyframes fade-in { from { opacity: 0; } }
+@keyframes fade-out { to { opacity: 0; } }
+";
+
+/// The pieces to draw for the board going from 'old' to 'new', as
+/// (square, color, piece, animation), driven by 'Position::changes'.
+pub fn animated_pieces(old: &Position, new: &Position) -> Vec<(Square, Color, Piece, Animation)> {
+    let mut pieces: Vec<_> = placed_pieces(new)
+        .into_iter()
+        .map(|(square, color, piece)| (square, color, piece, Animation::Still))
+        .collect();
+
+    for change in old.changes(new) {
+        let (square, animation) = match change {
+            BoardChange::Move(from, dest) => (dest, Animation::Slide(from)),
+            BoardChange::Add(_, square, _) => (square, Animation::FadeIn),
+            BoardChange::Remove(square) => {
+                if let Some((color, piece)) = old.piece_at(square) {
+                    pieces.push((square, color, piece, Animation::FadeOut));
+                }
+
+                continue;
+            }
+        };
+
+        // the piece taking the square, not the one fading out of it.
+        for placed in pieces.iter_mut() {
+            if placed.0 == square && placed.3 != Animation::FadeOut {
+                placed.3 = animation;
+            }
+        }
+    }
+
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animations(before: &str, after: &str) -> Vec<(Square, Color, Piece, Animation)> {
+        let old = BoardState::from_fen(before).unwrap().position();
+        let new = BoardState::from_fen(after).unwrap().position();
+
+        animated_pieces(&old, &new)
+            .into_iter()
+            .filter(|(_, _, _, animation)| *animation != Animation::Still)
+            .collect()
+    }
+
+    #[test]
+    fn animated_pieces_castle() {
+        let moved = animations(
+            "4k3/8/8/8/8/8/8/4K2R w K - 0 1",
+            "4k3/8/8/8/8/8/8/5RK1 b - - 1 1",
+        );
+
+        assert_eq!(moved.len(), 2);
+        assert!(moved.contains(&(
+            Square::G1,
+            Color::White,
+            Piece::King,
+            Animation::Slide(Square::E1)
+        )));
+        assert!(moved.contains(&(
+            Square::F1,
+            Color::White,
+            Piece::Rook,
+            Animation::Slide(Square::H1)
+        )));
+    }
+
+    #[test]
+    fn animated_pieces_capture_promotion() {
+        let moved = animations(
+            "3rk3/2P5/8/8/8/8/8/4K3 w - - 0 1",
+            "3Qk3/8/8/8/8/8/8/4K3 b - - 0 1",
+        );
+
+        assert_eq!(moved.len(), 3);
+        assert!(moved.contains(&(Square::C7, Color::White, Piece::Pawn, Animation::FadeOut)));
+        assert!(moved.contains(&(Square::D8, Color::Black, Piece::Rook, Animation::FadeOut)));
+        assert!(moved.contains(&(Square::D8, Color::White, Piece::Queen, Animation::FadeIn)));
+    }
+
+    #[test]
+    fn animation_style() {
+        assert_eq!(Animation::Still.style(Square::E4, false), "");
+        assert!(Animation::Slide(Square::E2)
+            .style(Square::E4, false)
+            .starts_with("--dx: 0px; --dy: 180px;"));
+        assert!(Animation::Slide(Square::E2)
+            .style(Square::E4, true)
+            .starts_with("--dx: 0px; --dy: -180px;"));
+    }
+}
@@ -0,0 +1,137 @@
+use super::*;
+
+/// The length and width of an arrow's head.
+const HEAD_SIZE: f32 = 30.0;
+
+/// A mark drawn over the board, in any SVG color like "green" or "#ff000080".
+#[derive(Clone, Debug, PartialEq)]
+pub enum Annotation {
+    /// An arrow from the first square to the second.
+    Arrow(Square, Square, String),
+    /// A ring around the square.
+    Circle(Square, String),
+}
+
+#[component]
+pub fn Annotations(
+    annotations: ReadOnlySignal<Vec<Annotation>>,
+    flipped: ReadOnlySignal<bool>,
+) -> Element {
+    let flipped = flipped();
+
+    rsx! {
+        g {
+            pointer_events: "none",
+            for annotation in annotations() {
+                match annotation {
+                    Annotation::Arrow(from, dest, color) => rsx! {
+                        if let Some(arrow) = Arrow::new(from, dest, flipped) {
+                            line {
+                                x1: "{arrow.start.0}",
+                                y1: "{arrow.start.1}",
+                                x2: "{arrow.end.0}",
+                                y2: "{arrow.end.1}",
+                                stroke: "{color}",
+                                stroke_width: "14",
+                            }
+                            polygon {
+                                points: "{arrow.points()}",
+                                fill: "{color}",
+                            }
+                        }
+                    },
+                    Annotation::Circle(square, color) => rsx! {
+                        circle {
+                            cx: "{square_center(square, flipped).0}",
+                            cy: "{square_center(square, flipped).1}",
+                            r: "41",
+                            fill: "none",
+                            stroke: "{color}",
+                            stroke_width: "6",
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Where an arrow between two squares is drawn.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Arrow {
+    /// The start of the shaft, at the center of the first square.
+    pub start: (f32, f32),
+    /// The end of the shaft, where the head begins.
+    pub end: (f32, f32),
+    /// The corners of the head, the first being the tip at the
+    /// center of the second square.
+    pub head: [(f32, f32); 3],
+}
+
+impl Arrow {
+    /// The arrow from the center of one square to the other.
+    /// None if the squares are the same.
+    pub fn new(from: Square, dest: Square, flipped: bool) -> Option<Self> {
+        if from == dest {
+            return None;
+        }
+
+        let (x1, y1) = square_center(from, flipped);
+        let (x2, y2) = square_center(dest, flipped);
+        let (x1, y1, x2, y2) = (x1 as f32, y1 as f32, x2 as f32, y2 as f32);
+
+        // the unit vector along the arrow, and the one across it.
+        let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+        let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+        let (nx, ny) = (-dy, dx);
+
+        let end = (x2 - dx * HEAD_SIZE, y2 - dy * HEAD_SIZE);
+        let half = HEAD_SIZE / 2.0;
+
+        Some(Self {
+            start: (x1, y1),
+            end,
+            head: [
+                (x2, y2),
+                (end.0 + nx * half, end.1 + ny * half),
+                (end.0 - nx * half, end.1 - ny * half),
+            ],
+        })
+    }
+
+    /// The head as the 'points' of an SVG polygon.
+    pub fn points(&self) -> String {
+        self.head
+            .iter()
+            .map(|(x, y)| format!("{x},{y}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrow_e2_e4() {
+        let arrow = Arrow::new(Square::E2, Square::E4, false).unwrap();
+
+        assert_eq!(arrow.start, (405.0, 585.0));
+        assert_eq!(arrow.end, (405.0, 435.0));
+        assert_eq!(arrow.head[0], (405.0, 405.0));
+        assert_eq!(arrow.points(), "405,405 420,435 390,435");
+    }
+
+    #[test]
+    fn arrow_flipped() {
+        let arrow = Arrow::new(Square::E2, Square::E4, true).unwrap();
+
+        // the arrow points down the board from black's side.
+        assert_eq!(arrow.start, (315.0, 135.0));
+        assert_eq!(arrow.end, (315.0, 285.0));
+        assert_eq!(arrow.head[0], (315.0, 315.0));
+
+        assert!(Arrow::new(Square::E2, Square::E2, true).is_none());
+    }
+}
@@ -0,0 +1,61 @@
+use super::*;
+use crate::chess_core::{File, Rank};
+
+#[component]
+pub fn Coordinates(flipped: ReadOnlySignal<bool>) -> Element {
+    rsx! {
+        g {
+            font_size: "16",
+            fill: "#888888",
+            for (label, x, y) in coordinate_labels(flipped()) {
+                text { key: "{label}", x: "{x}", y: "{y}", "{label}" }
+            }
+        }
+    }
+}
+
+/// The file letters along the bottom edge and the rank numbers along the left
+/// edge, as (label, x, y). Flipping the board reverses the order of both.
+pub fn coordinate_labels(flipped: bool) -> Vec<(char, u32, u32)> {
+    // the squares the labels sit in, along the bottom and left edges.
+    let (bottom, left) = match flipped {
+        false => (Rank::_1, File::A),
+        true => (Rank::_8, File::H),
+    };
+
+    let files = File::iter().map(|file| {
+        let (x, y) = square_origin(Square::new(file, bottom), flipped);
+        (
+            file.to_char_lower(),
+            x + SQUARE_SIZE - 12,
+            y + SQUARE_SIZE - 6,
+        )
+    });
+
+    let ranks = Rank::iter().map(|rank| {
+        let (x, y) = square_origin(Square::new(left, rank), flipped);
+        (rank.to_char(), x + 4, y + 18)
+    });
+
+    files.chain(ranks).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinate_labels_flipped() {
+        let labels = coordinate_labels(true);
+        assert_eq!(labels.len(), 16);
+
+        // the a-file is on the right and the first rank is at the top.
+        let (_, x, y) = labels.iter().find(|(label, _, _)| *label == 'a').unwrap();
+        assert!(*x > 630 && *y > 630);
+        let (_, x, y) = labels.iter().find(|(label, _, _)| *label == '1').unwrap();
+        assert!(*x < 90 && *y < 90);
+
+        let (_, x, y) = coordinate_labels(false)[0];
+        assert!(x < 90 && y > 630);
+    }
+}
@@ -0,0 +1,202 @@
+use super::*;
+
+/// The colors used to mark squares on the board.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HighlightColors {
+    /// The square of the selected piece.
+    pub selected: String,
+    /// The dot on a destination square that is empty.
+    pub quiet: String,
+    /// The ring on a destination square that captures.
+    pub capture: String,
+    /// The squares the last move was played from and to.
+    pub last_move: String,
+    /// The square of a king in check.
+    pub check: String,
+}
+
+impl Default for HighlightColors {
+    fn default() -> Self {
+        Self {
+            selected: "rgba(255, 255, 0, 0.4)".to_string(),
+            quiet: "rgba(0, 0, 0, 0.25)".to_string(),
+            capture: "rgba(0, 0, 0, 0.25)".to_string(),
+            last_move: "rgba(155, 199, 0, 0.4)".to_string(),
+            check: "rgba(255, 0, 0, 0.6)".to_string(),
+        }
+    }
+}
+
+#[component]
+pub fn Highlights(
+    state: ReadOnlySignal<BoardState>,
+    selected: ReadOnlySignal<Option<Square>>,
+    flipped: ReadOnlySignal<bool>,
+    colors: HighlightColors,
+) -> Element {
+    let from = selected()?;
+
+    let flipped = flipped();
+    let (x, y) = square_origin(from, flipped);
+
+    rsx! {
+        g {
+            rect {
+                x: "{x}",
+                y: "{y}",
+                width: "{SQUARE_SIZE}",
+                height: "{SQUARE_SIZE}",
+                fill: "{colors.selected}",
+            }
+            for (dest, capture) in destination_marks(&state(), from) {
+                if capture {
+                    circle {
+                        key: "{dest}",
+                        cx: "{square_center(dest, flipped).0}",
+                        cy: "{square_center(dest, flipped).1}",
+                        r: "40",
+                        fill: "none",
+                        stroke: "{colors.capture}",
+                        stroke_width: "8",
+                    }
+                } else {
+                    circle {
+                        key: "{dest}",
+                        cx: "{square_center(dest, flipped).0}",
+                        cy: "{square_center(dest, flipped).1}",
+                        r: "14",
+                        fill: "{colors.quiet}",
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Shades the squares of the last move, and the king of the side to move if it is in check.
+#[component]
+pub fn SquareHighlights(
+    state: ReadOnlySignal<BoardState>,
+    last_move: Option<(Square, Square)>,
+    flipped: ReadOnlySignal<bool>,
+    colors: HighlightColors,
+) -> Element {
+    let flipped = flipped();
+
+    rsx! {
+        g {
+            for (square, highlight) in square_highlights(&state(), last_move) {
+                rect {
+                    key: "{square}-{highlight:?}",
+                    x: "{square_origin(square, flipped).0}",
+                    y: "{square_origin(square, flipped).1}",
+                    width: "{SQUARE_SIZE}",
+                    height: "{SQUARE_SIZE}",
+                    fill: match highlight {
+                        SquareHighlight::LastMove => colors.last_move.clone(),
+                        SquareHighlight::Check => colors.check.clone(),
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Why a square is shaded by 'SquareHighlights'.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SquareHighlight {
+    LastMove,
+    Check,
+}
+
+/// The squares to shade, check last so it's drawn over the last move.
+pub fn square_highlights(
+    state: &BoardState,
+    last_move: Option<(Square, Square)>,
+) -> Vec<(Square, SquareHighlight)> {
+    let mut highlights = Vec::new();
+
+    if let Some((from, dest)) = last_move {
+        highlights.push((from, SquareHighlight::LastMove));
+        highlights.push((dest, SquareHighlight::LastMove));
+    }
+
+    if state.generator().is_check() {
+        let position = state.position();
+
+        if let Some(king) = (position.kings() & position.color_mask(state.turn())).first() {
+            highlights.push((king, SquareHighlight::Check));
+        }
+    }
+
+    highlights
+}
+
+/// The legal destinations of the piece on the square, as (dest, capture).
+pub fn destination_marks(state: &BoardState, from: Square) -> Vec<(Square, bool)> {
+    let generator = state.generator();
+    let captures = generator.generate_captures(from);
+
+    generator
+        .generate(from)
+        .into_iter()
+        .map(|dest| (dest, captures.has(dest)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn destination_marks_knight() {
+        let marks = destination_marks(&BoardState::default(), Square::G1);
+        assert_eq!(marks.len(), 2);
+        assert!(marks.contains(&(Square::F3, false)));
+        assert!(marks.contains(&(Square::H3, false)));
+    }
+
+    #[test]
+    fn destination_marks_capture() {
+        let state = BoardState::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let marks = destination_marks(&state, Square::E4);
+
+        assert_eq!(marks.len(), 2);
+        assert!(marks.contains(&(Square::D5, true)));
+        assert!(marks.contains(&(Square::E5, false)));
+
+        // pieces of the side not to move have no destinations.
+        assert!(destination_marks(&state, Square::D5).is_empty());
+    }
+
+    #[test]
+    fn square_highlights_last_move() {
+        let state = BoardState::default();
+        assert!(square_highlights(&state, None).is_empty());
+
+        assert_eq!(
+            square_highlights(&state, Some((Square::G1, Square::F3))),
+            [
+                (Square::G1, SquareHighlight::LastMove),
+                (Square::F3, SquareHighlight::LastMove)
+            ]
+        );
+    }
+
+    #[test]
+    fn square_highlights_check() {
+        // fool's mate, black's queen checks the white king.
+        let state =
+            BoardState::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+                .unwrap();
+
+        assert_eq!(
+            square_highlights(&state, Some((Square::D8, Square::H4))),
+            [
+                (Square::D8, SquareHighlight::LastMove),
+                (Square::H4, SquareHighlight::LastMove),
+                (Square::E1, SquareHighlight::Check)
+            ]
+        );
+    }
+}
@@ -1,15 +1,228 @@
+mod animation;
+mod annotations;
+mod coordinates;
+mod highlights;
+mod pieces;
+mod promotion;
+mod selection;
 mod squares;
 
 use super::*;
+use crate::chess_core::{BoardState, Piece, Square};
+use animation::*;
+pub use annotations::*;
+use coordinates::*;
+pub use highlights::*;
+pub use pieces::PieceSet;
+use pieces::*;
+use promotion::*;
+use selection::*;
 use squares::*;
 
+/// The width and height of a square in the board's viewBox.
+pub const SQUARE_SIZE: u32 = 90;
+
 #[component]
-pub fn Board(flipped: ReadOnlySignal<bool>) -> Element {
+pub fn Board(
+    state: ReadOnlySignal<BoardState>,
+    flipped: ReadOnlySignal<bool>,
+    on_move: EventHandler<(Square, Square, Option<Piece>)>,
+    /// Arrows and circles drawn over the pieces.
+    annotations: ReadOnlySignal<Vec<Annotation>>,
+    #[props(default)] highlight_colors: HighlightColors,
+    /// The (from, dest) of the move that led to the state, to highlight.
+    #[props(default)]
+    last_move: Option<(Square, Square)>,
+    /// Slide and fade the pieces when the state changes, instead of snapping.
+    #[props(default = true)]
+    animate: bool,
+    /// The graphics of the pieces, Unicode glyphs by default.
+    #[props(default)]
+    piece_set: PieceSet,
+) -> Element {
+    let mut selected = use_signal(|| None::<Square>);
+    let mut promoting = use_signal(|| None::<(Square, Square)>);
+
+    let mut click = move |square: Square| match Click::new(&state(), selected(), square) {
+        Click::Select(square) => selected.set(Some(square)),
+        Click::Deselect => selected.set(None),
+        Click::Move(from, dest) => {
+            selected.set(None);
+            on_move.call((from, dest, None));
+        }
+        Click::Promote(from, dest) => {
+            selected.set(None);
+            promoting.set(Some((from, dest)));
+        }
+    };
+
+    let pick = move |piece: Option<Piece>| {
+        if let (Some((from, dest)), Some(piece)) = (promoting(), piece) {
+            on_move.call((from, dest, Some(piece)));
+        }
+
+        promoting.set(None);
+    };
+
     rsx! {
         svg {
             width: "100%",
             height: "100%",
             view_box: "0 0 720 720",
+            Coordinates { flipped }
+            SquareHighlights {
+                state,
+                last_move,
+                flipped,
+                colors: highlight_colors.clone(),
+            }
+            Highlights {
+                state,
+                selected,
+                flipped,
+                colors: highlight_colors,
+            }
+            Pieces {
+                state,
+                flipped,
+                animate,
+                piece_set: piece_set.clone(),
+            }
+            Annotations { annotations, flipped }
+
+            // transparent squares on top of everything to catch clicks.
+            for square in Square::iter() {
+                rect {
+                    key: "{square}",
+                    x: "{square_origin(square, flipped()).0}",
+                    y: "{square_origin(square, flipped()).1}",
+                    width: "{SQUARE_SIZE}",
+                    height: "{SQUARE_SIZE}",
+                    fill: "transparent",
+                    onclick: move |_| click(square),
+                }
+            }
+
+            if let Some((_, dest)) = promoting() {
+                PromotionPicker {
+                    dest,
+                    color: state().turn(),
+                    flipped,
+                    piece_set: piece_set.clone(),
+                    on_pick: pick,
+                }
+            }
+        }
+    }
+}
+
+/// The top-left corner of the square in the board's viewBox. White
+/// sits at the bottom, unless the board is flipped.
+pub fn square_origin(square: Square, flipped: bool) -> (u32, u32) {
+    let file = square.file() as u32;
+    let rank = square.rank() as u32;
+
+    if flipped {
+        ((7 - file) * SQUARE_SIZE, rank * SQUARE_SIZE)
+    } else {
+        (file * SQUARE_SIZE, (7 - rank) * SQUARE_SIZE)
+    }
+}
+
+/// The center of the square in the board's viewBox.
+pub fn square_center(square: Square, flipped: bool) -> (u32, u32) {
+    let (x, y) = square_origin(square, flipped);
+    (x + SQUARE_SIZE / 2, y + SQUARE_SIZE / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square_origin_unflipped() {
+        assert_eq!(square_origin(Square::A8, false), (0, 0));
+        assert_eq!(square_origin(Square::A1, false), (0, 630));
+        assert_eq!(square_origin(Square::H1, false), (630, 630));
+        assert_eq!(square_center(Square::E4, false), (405, 405));
+    }
+
+    #[test]
+    fn square_origin_flipped() {
+        // black sits at the bottom, so a1 is in the top-right corner.
+        assert_eq!(square_origin(Square::A1, true), (630, 0));
+        assert_eq!(square_origin(Square::H8, true), (0, 630));
+        assert_eq!(square_center(Square::E4, true), (315, 315));
+    }
+
+    #[test]
+    fn promotion_picker_plays_move() {
+        use dioxus::dioxus_core::{ElementId, Mutation, Mutations};
+        use dioxus::html::{
+            set_event_converter, PlatformEventData, SerializedHtmlEventConverter,
+            SerializedMouseData,
+        };
+        use std::rc::Rc;
+        use std::sync::Mutex;
+
+        static PLAYED: Mutex<Vec<(Square, Square, Option<Piece>)>> = Mutex::new(Vec::new());
+
+        fn Root() -> Element {
+            let state =
+                use_signal(|| BoardState::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap());
+            let flipped = use_signal(|| false);
+            let annotations = use_signal(Vec::new);
+
+            rsx! {
+                Board {
+                    state,
+                    flipped,
+                    on_move: move |mv| PLAYED.lock().unwrap().push(mv),
+                    annotations,
+                    animate: false,
+                }
+            }
         }
+
+        // the ids of the elements listening for clicks, in the order they were created.
+        fn click_listeners(mutations: Mutations) -> Vec<ElementId> {
+            mutations
+                .edits
+                .into_iter()
+                .filter_map(|edit| match edit {
+                    Mutation::NewEventListener { name, id } if name == "click" => Some(id),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        fn click(dom: &mut VirtualDom, id: ElementId) -> Vec<ElementId> {
+            let data = PlatformEventData::new(Box::<SerializedMouseData>::default());
+            dom.handle_event("click", Rc::new(data), id, true);
+            click_listeners(dom.render_immediate_to_vec())
+        }
+
+        set_event_converter(Box::new(SerializedHtmlEventConverter));
+
+        let mut dom = VirtualDom::new(Root);
+
+        // one transparent square per square catches the clicks.
+        let squares = click_listeners(dom.rebuild_to_vec());
+        assert_eq!(squares.len(), 64);
+
+        // select the pawn, then move it to the back rank, which opens
+        // the picker instead of playing the move.
+        click(&mut dom, squares[Square::E7 as usize]);
+        let picker = click(&mut dom, squares[Square::E8 as usize]);
+        assert!(PLAYED.lock().unwrap().is_empty());
+
+        // the picker's backdrop, then one choice per promotion piece.
+        assert_eq!(picker.len(), 5);
+        click(&mut dom, picker[2]);
+
+        assert_eq!(
+            *PLAYED.lock().unwrap(),
+            [(Square::E7, Square::E8, Some(Piece::Rook))]
+        );
     }
 }
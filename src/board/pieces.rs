@@ -0,0 +1,180 @@
+use super::*;
+use crate::chess_core::{Color, Piece, Position};
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[component]
+pub fn Pieces(
+    state: ReadOnlySignal<BoardState>,
+    flipped: ReadOnlySignal<bool>,
+    animate: bool,
+    piece_set: PieceSet,
+) -> Element {
+    let flipped = flipped();
+
+    // the position drawn before the state changed, which the pieces animate from.
+    let previous = use_hook(|| Rc::new(Cell::new(state.peek().position())));
+    let transition = use_memo(move || {
+        let new = state().position();
+        (previous.replace(new), new)
+    });
+
+    let (old, new) = transition();
+    let pieces = match animate {
+        true => animated_pieces(&old, &new),
+        false => animated_pieces(&new, &new),
+    };
+
+    rsx! {
+        style { {KEYFRAMES} }
+        g {
+            for (square, color, piece, animation) in pieces {
+                g {
+                    key: "{square}-{color:?}-{piece:?}-{animation:?}",
+                    style: animation.style(square, flipped),
+                    PieceGlyph {
+                        square,
+                        color,
+                        piece,
+                        flipped,
+                        piece_set: piece_set.clone(),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How the pieces are drawn.
+#[derive(Clone, Debug, Default)]
+pub enum PieceSet {
+    /// The Unicode chess glyphs, filled with the color of the piece.
+    #[default]
+    Unicode,
+    /// An image for every piece, from the href the function returns.
+    Images(fn(Color, Piece) -> String),
+}
+
+impl PartialEq for PieceSet {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Unicode, Self::Unicode) => true,
+            // props only need to notice a different function being passed in.
+            (Self::Images(a), Self::Images(b)) => std::ptr::fn_addr_eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+#[component]
+pub fn PieceGlyph(
+    square: Square,
+    color: Color,
+    piece: Piece,
+    flipped: bool,
+    piece_set: PieceSet,
+) -> Element {
+    if let PieceSet::Images(href) = piece_set {
+        let (x, y) = square_origin(square, flipped);
+
+        return rsx! {
+            image {
+                href: href(color, piece),
+                x: "{x}",
+                y: "{y}",
+                width: "{SQUARE_SIZE}",
+                height: "{SQUARE_SIZE}",
+            }
+        };
+    }
+
+    let (x, y) = square_center(square, flipped);
+
+    // the filled glyphs are used for both colors, so
+    // white pieces aren't see-through on dark squares.
+    let (fill, stroke) = match color {
+        Color::White => ("#ffffff", "#000000"),
+        Color::Black => ("#000000", "#000000"),
+    };
+
+    rsx! {
+        text {
+            x: "{x}",
+            y: "{y}",
+            font_size: "72",
+            text_anchor: "middle",
+            dominant_baseline: "central",
+            fill,
+            stroke,
+            stroke_width: "1.5",
+            "{piece.unicode(Color::Black)}"
+        }
+    }
+}
+
+/// Every piece on the board, as (square, color, piece).
+pub fn placed_pieces(position: &Position) -> Vec<(Square, Color, Piece)> {
+    let mut pieces = Vec::new();
+
+    for (piece, mask) in position.pieces() {
+        for color in [Color::White, Color::Black] {
+            for square in mask & position.color_mask(color) {
+                pieces.push((square, color, piece));
+            }
+        }
+    }
+
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placed_pieces_start() {
+        let pieces = placed_pieces(&BoardState::default().position());
+
+        assert_eq!(pieces.len(), 32);
+        assert_eq!(
+            pieces
+                .iter()
+                .filter(|(_, color, _)| *color == Color::White)
+                .count(),
+            16
+        );
+        assert!(pieces.contains(&(Square::E1, Color::White, Piece::King)));
+        assert!(pieces.contains(&(Square::D8, Color::Black, Piece::Queen)));
+    }
+
+    #[test]
+    fn piece_set_images() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        fn href(color: Color, piece: Piece) -> String {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            format!("/pieces/{}.svg", piece.id(color))
+        }
+
+        fn Root() -> Element {
+            let state = use_signal(BoardState::default);
+            let flipped = use_signal(|| false);
+
+            rsx! {
+                Pieces {
+                    state,
+                    flipped,
+                    animate: false,
+                    piece_set: PieceSet::Images(href),
+                }
+            }
+        }
+
+        let mut dom = VirtualDom::new(Root);
+        dom.rebuild_in_place();
+
+        assert_eq!(CALLS.load(Ordering::SeqCst), 32);
+    }
+}
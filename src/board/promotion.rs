@@ -0,0 +1,90 @@
+use super::*;
+use crate::chess_core::Color;
+
+/// Lets the user pick the piece a pawn promotes to. The choices stand in a
+/// column from the promotion square towards the middle of the board.
+/// Picks None if the user clicks outside of the choices.
+#[component]
+pub fn PromotionPicker(
+    dest: Square,
+    color: Color,
+    flipped: ReadOnlySignal<bool>,
+    piece_set: PieceSet,
+    on_pick: EventHandler<Option<Piece>>,
+) -> Element {
+    let flipped = flipped();
+
+    rsx! {
+        g {
+            rect {
+                width: "720",
+                height: "720",
+                fill: "rgba(0, 0, 0, 0.5)",
+                onclick: move |_| on_pick.call(None),
+            }
+            for (piece, square) in promotion_squares(dest, color) {
+                rect {
+                    key: "{piece:?}",
+                    x: "{square_origin(square, flipped).0}",
+                    y: "{square_origin(square, flipped).1}",
+                    width: "{SQUARE_SIZE}",
+                    height: "{SQUARE_SIZE}",
+                    fill: "#f0f0f0",
+                }
+                PieceGlyph {
+                    square,
+                    color,
+                    piece,
+                    flipped,
+                    piece_set: piece_set.clone(),
+                }
+                rect {
+                    x: "{square_origin(square, flipped).0}",
+                    y: "{square_origin(square, flipped).1}",
+                    width: "{SQUARE_SIZE}",
+                    height: "{SQUARE_SIZE}",
+                    fill: "transparent",
+                    onclick: move |_| on_pick.call(Some(piece)),
+                }
+            }
+        }
+    }
+}
+
+/// The square each promotion choice is drawn on, starting at the
+/// promotion square and going back towards the promoting side.
+pub fn promotion_squares(dest: Square, color: Color) -> [(Piece, Square); 4] {
+    let mut squares = [(Piece::Queen, dest); 4];
+
+    for (i, piece) in Piece::PROMOTIONS.into_iter().enumerate() {
+        let square = dest
+            .try_offset(0, -color.pawn_dir() * i as i8)
+            .expect("promotions happen on the back rank!");
+
+        squares[i] = (piece, square);
+    }
+
+    squares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn promotion_squares_white() {
+        let squares = promotion_squares(Square::E8, Color::White);
+
+        assert_eq!(squares[0], (Piece::Queen, Square::E8));
+        assert_eq!(squares[3], (Piece::Knight, Square::E5));
+    }
+
+    #[test]
+    fn promotion_squares_black() {
+        let squares = promotion_squares(Square::A1, Color::Black);
+
+        assert_eq!(squares[0], (Piece::Queen, Square::A1));
+        assert_eq!(squares[1], (Piece::Rook, Square::A2));
+        assert_eq!(squares[3], (Piece::Knight, Square::A4));
+    }
+}
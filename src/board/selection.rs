@@ -0,0 +1,92 @@
+use super::*;
+
+/// What a click on a square does, given the square that was selected before it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Click {
+    /// Select a piece of the side to move.
+    Select(Square),
+    /// Clear the selection.
+    Deselect,
+    /// Play the selected piece to a legal destination, as (from, dest).
+    Move(Square, Square),
+    /// Like Move, but the pawn needs to be told what to promote to.
+    Promote(Square, Square),
+}
+
+impl Click {
+    /// The first click selects a piece of the side to move, and the second plays it
+    /// if the square is a legal destination. Clicking another piece of the side
+    /// to move selects it instead, and clicking anything else deselects.
+    pub fn new(state: &BoardState, selected: Option<Square>, square: Square) -> Self {
+        let generator = state.generator();
+
+        if let Some(from) = selected {
+            if generator.generate(from).has(square) {
+                if state.move_requires_promotion(from, square) {
+                    return Self::Promote(from, square);
+                }
+
+                return Self::Move(from, square);
+            }
+
+            if from == square {
+                return Self::Deselect;
+            }
+        }
+
+        match state.position().piece_at(square) {
+            Some((color, _)) if color == state.turn() => Self::Select(square),
+            _ => Self::Deselect,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn click_to_move() {
+        let state = BoardState::default();
+
+        let first = Click::new(&state, None, Square::E2);
+        assert_eq!(first, Click::Select(Square::E2));
+        assert_eq!(
+            Click::new(&state, Some(Square::E2), Square::E4),
+            Click::Move(Square::E2, Square::E4)
+        );
+
+        // another piece of the side to move changes the selection.
+        assert_eq!(
+            Click::new(&state, Some(Square::E2), Square::G1),
+            Click::Select(Square::G1)
+        );
+
+        // illegal destinations, the opponent's pieces and the
+        // selected piece itself all clear the selection.
+        for square in [Square::E5, Square::E7, Square::E2] {
+            assert_eq!(
+                Click::new(&state, Some(Square::E2), square),
+                Click::Deselect
+            );
+        }
+        assert_eq!(Click::new(&state, None, Square::E7), Click::Deselect);
+    }
+
+    #[test]
+    fn click_to_promote() {
+        let state = BoardState::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            Click::new(&state, Some(Square::B7), Square::B8),
+            Click::Promote(Square::B7, Square::B8)
+        );
+
+        // the picker offers every promotion the pawn can make.
+        for (piece, _) in promotion_squares(Square::B8, state.turn()) {
+            assert!(state
+                .legal_moves()
+                .contains(&(Square::B7, Square::B8, Some(piece))));
+        }
+    }
+}
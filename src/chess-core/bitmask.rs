@@ -1,9 +1,13 @@
 use std::ops::*;
 
+use crate::cached;
 use crate::square::{File, Rank, Square};
 
 /// A 64-bit number representing a selection of squares on a board.
+/// With the 'serde' feature, this serializes as the underlying u64.
 #[derive(Copy, Clone, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Bitmask(pub u64);
 
 impl Bitmask {
@@ -98,26 +102,37 @@ impl Bitmask {
 
     /// The intersection of two bitboards.
     /// The resulting bitmask has all the 1s of self, without the 1s of other.
+    /// NOTE: despite the name, this is the set difference, same as 'self - other'.
     pub fn intersection(self, other: Self) -> Self {
         self & !other
     }
 
     /// Returns true if self and other intersect (share any 1s)
     pub fn intersects(self, other: Self) -> bool {
-        self.intersection(other) != self
+        (self.0 & other.0) != 0
     }
 
-    /// Returns a bitmask of the intersection if they intersect at all.
+    /// Returns a bitmask of the shared squares if they intersect at all.
     pub fn intersects_then(self, other: Self) -> Option<Bitmask> {
-        let intersection = self.intersection(other);
+        let shared = self & other;
 
-        if intersection != self {
-            Some(intersection)
-        } else {
+        if shared.is_empty() {
             None
+        } else {
+            Some(shared)
         }
     }
 
+    /// Returns true if every 1 in self is also a 1 in other.
+    pub fn is_subset(self, other: Self) -> bool {
+        (self.0 & other.0) == self.0
+    }
+
+    /// Returns true if every 1 in other is also a 1 in self.
+    pub fn contains_all(self, other: Self) -> bool {
+        other.is_subset(self)
+    }
+
     /// This bitmask, with all bits in the rank set to 1.
     pub fn with_rank(self, rank: Rank) -> Self {
         self | match rank {
@@ -146,7 +161,9 @@ impl Bitmask {
         }
     }
 
-    pub fn with_shared(mut self, sq1: Square, sq2: Square) -> Self {
+    /// This bitmask, with all bits in the rank, file, diagonal, or anti-diagonal
+    /// shared by both squares set to 1. If they share no line, nothing changes.
+    pub fn with_shared(self, sq1: Square, sq2: Square) -> Self {
         if sq1.shares_orthogonal(sq2) {
             if sq1.file() == sq2.file() {
                 return self.with_file(sq1.file());
@@ -154,11 +171,79 @@ impl Bitmask {
                 return self.with_rank(sq1.rank());
             }
         } else if sq1.shares_diagonal(sq2) {
+            // if the file and rank change by the same amount, the squares are on an
+            // a1-h8 style diagonal, otherwise they are on an a8-h1 style anti-diagonal.
+            if sq1.file() as i8 - sq2.file() as i8 == sq1.rank() as i8 - sq2.rank() as i8 {
+                return self | Bitmask(cached::DIAGONAL[sq1 as usize]);
+            } else {
+                return self | Bitmask(cached::ANTIDIAGONAL[sq1 as usize]);
+            }
         }
 
         self
     }
 
+    /// Shift every square one rank up, towards rank 8.
+    /// Squares on rank 8 fall off the board.
+    pub fn shift_north(self) -> Self {
+        Self(self.0 << 8)
+    }
+
+    /// Shift every square one rank down, towards rank 1.
+    /// Squares on rank 1 fall off the board.
+    pub fn shift_south(self) -> Self {
+        Self(self.0 >> 8)
+    }
+
+    /// Shift every square one file right, towards the H file.
+    /// Squares on the H file fall off the board instead of
+    /// wrapping around to the A file of the next rank.
+    pub fn shift_east(self) -> Self {
+        Self(self.0 << 1) & !Self::FILEA
+    }
+
+    /// Shift every square one file left, towards the A file.
+    /// Squares on the A file fall off the board instead of
+    /// wrapping around to the H file of the previous rank.
+    pub fn shift_west(self) -> Self {
+        Self(self.0 >> 1) & !Self::FILEH
+    }
+
+    /// Shift every square one step up and to the right.
+    pub fn shift_ne(self) -> Self {
+        self.shift_north().shift_east()
+    }
+
+    /// Shift every square one step up and to the left.
+    pub fn shift_nw(self) -> Self {
+        self.shift_north().shift_west()
+    }
+
+    /// Shift every square one step down and to the right.
+    pub fn shift_se(self) -> Self {
+        self.shift_south().shift_east()
+    }
+
+    /// Shift every square one step down and to the left.
+    pub fn shift_sw(self) -> Self {
+        self.shift_south().shift_west()
+    }
+
+    /// Mirror the mask across the horizontal midline, so
+    /// rank 1 swaps with rank 8, rank 2 with rank 7, etc.
+    pub fn mirror_vertical(self) -> Self {
+        // each byte is a rank, so reversing the bytes reverses the ranks.
+        Self(self.0.swap_bytes())
+    }
+
+    /// Mirror the mask across the vertical midline, so
+    /// the A file swaps with the H file, B with G, etc.
+    pub fn mirror_horizontal(self) -> Self {
+        // reversing all the bits mirrors both the files and the ranks,
+        // so swap the bytes to put the ranks back where they were.
+        Self(self.0.reverse_bits().swap_bytes())
+    }
+
     /// The square in the mask with the lowest value.
     /// Where H8=63 and A1=0
     /// This function is particularly useful, since
@@ -217,8 +302,16 @@ impl Iterator for BitmaskIter {
             None
         }
     }
+
+    // the remaining length is the number of squares left in the mask.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.0.count() as usize;
+        (count, Some(count))
+    }
 }
 
+impl ExactSizeIterator for BitmaskIter {}
+
 impl DoubleEndedIterator for BitmaskIter {
     // Backwards, H8-G8-F8...C1-B1-A1
     fn next_back(&mut self) -> Option<Self::Item> {
@@ -235,6 +328,22 @@ impl DoubleEndedIterator for BitmaskIter {
     }
 }
 
+impl FromIterator<Square> for Bitmask {
+    fn from_iter<T: IntoIterator<Item = Square>>(iter: T) -> Self {
+        let mut mask = Bitmask::EMPTY;
+        mask.extend(iter);
+        mask
+    }
+}
+
+impl Extend<Square> for Bitmask {
+    fn extend<T: IntoIterator<Item = Square>>(&mut self, iter: T) {
+        for square in iter {
+            self.set(square);
+        }
+    }
+}
+
 impl BitOr for Bitmask {
     type Output = Self;
 
@@ -277,6 +386,20 @@ impl BitXorAssign for Bitmask {
     }
 }
 
+impl Sub for Bitmask {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 & !rhs.0)
+    }
+}
+
+impl SubAssign for Bitmask {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 &= !rhs.0
+    }
+}
+
 impl Not for Bitmask {
     type Output = Self;
 
@@ -304,6 +427,33 @@ impl std::fmt::Debug for Bitmask {
     }
 }
 
+impl std::fmt::Display for Bitmask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // rank 8 on top, the same orientation as Debug.
+        for rank in Rank::iter().rev() {
+            write!(f, "{}", rank.to_char())?;
+
+            for file in File::iter() {
+                if self.has(Square::new(file, rank)) {
+                    write!(f, " X")?;
+                } else {
+                    write!(f, " .")?;
+                }
+            }
+
+            writeln!(f)?;
+        }
+
+        // file labels along the bottom.
+        write!(f, " ")?;
+        for file in File::iter() {
+            write!(f, " {}", file.to_char_lower())?;
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -383,6 +533,273 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bitmask_intersects_disjoint() {
+        assert!(!Bitmask::RANK1.intersects(Bitmask::RANK2));
+        assert!(!Bitmask::EMPTY.intersects(Bitmask::RANK1));
+        assert_eq!(Bitmask::RANK1.intersects_then(Bitmask::RANK2), None);
+    }
+
+    #[test]
+    fn bitmask_intersects_full_overlap() {
+        assert!(Bitmask::RANK1.intersects(Bitmask::RANK1));
+        assert_eq!(
+            Bitmask::RANK1.intersects_then(Bitmask::RANK1),
+            Some(Bitmask::RANK1)
+        );
+    }
+
+    #[test]
+    fn bitmask_intersects_partial_overlap() {
+        assert!(Bitmask::RANK1.intersects(Bitmask::FILEA));
+        assert!(Bitmask::from(0b0110).intersects(Bitmask::from(0b1100)));
+        assert_eq!(
+            Bitmask::RANK1.intersects_then(Bitmask::FILEA),
+            Some(Square::A1.mask())
+        );
+        assert_eq!(
+            Bitmask::from(0b0110).intersects_then(Bitmask::from(0b1100)),
+            Some(Bitmask::from(0b0100))
+        );
+    }
+
+    #[test]
+    fn bitmask_shift_corner() {
+        assert_eq!(Square::A1.mask().shift_north(), Square::A2.mask());
+        assert_eq!(Square::A1.mask().shift_east(), Square::B1.mask());
+        assert_eq!(Square::A1.mask().shift_ne(), Square::B2.mask());
+        assert_eq!(Square::A1.mask().shift_south(), Bitmask::EMPTY);
+        assert_eq!(Square::A1.mask().shift_west(), Bitmask::EMPTY);
+        assert_eq!(Square::A1.mask().shift_nw(), Bitmask::EMPTY);
+        assert_eq!(Square::A1.mask().shift_se(), Bitmask::EMPTY);
+        assert_eq!(Square::H8.mask().shift_north(), Bitmask::EMPTY);
+        assert_eq!(Square::H8.mask().shift_east(), Bitmask::EMPTY);
+        assert_eq!(Square::H8.mask().shift_sw(), Square::G7.mask());
+        assert_eq!(Square::H1.mask().shift_east(), Bitmask::EMPTY);
+        assert_eq!(Square::A8.mask().shift_west(), Bitmask::EMPTY);
+    }
+
+    #[test]
+    fn bitmask_shift_rank() {
+        assert_eq!(Bitmask::RANK1.shift_north(), Bitmask::RANK2);
+        assert_eq!(Bitmask::RANK1.shift_south(), Bitmask::EMPTY);
+        assert_eq!(Bitmask::RANK8.shift_north(), Bitmask::EMPTY);
+        assert_eq!(
+            Bitmask::RANK4.shift_east(),
+            Bitmask::RANK4.without(Square::A4)
+        );
+        assert_eq!(
+            Bitmask::RANK4.shift_west(),
+            Bitmask::RANK4.without(Square::H4)
+        );
+        assert_eq!(
+            Bitmask::RANK4.shift_ne(),
+            Bitmask::RANK5.without(Square::A5)
+        );
+        assert_eq!(
+            Bitmask::RANK4.shift_sw(),
+            Bitmask::RANK3.without(Square::H3)
+        );
+    }
+
+    #[test]
+    fn bitmask_display() {
+        let expected = "8 . . . . . . . .\n\
+                        7 . . . . . . . .\n\
+                        6 . . . . . . . .\n\
+                        5 . . . . . . . .\n\
+                        4 . . . . . . . .\n\
+                        3 . . . . . . . .\n\
+                        2 . . . . . . . .\n\
+                        1 X X X X X X X X\n  \
+                        a b c d e f g h";
+
+        assert_eq!(Bitmask::RANK1.to_string(), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn bitmask_serde_round_trip() {
+        let mask = Bitmask::RANK1.with(Square::H8);
+        let json = serde_json::to_string(&mask).unwrap();
+
+        assert_eq!(json, mask.0.to_string());
+        assert_eq!(serde_json::from_str::<Bitmask>(&json).unwrap(), mask);
+        assert_eq!(
+            serde_json::from_str::<Bitmask>("18446744073709551615").unwrap(),
+            Bitmask(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn bitmask_collect() {
+        let squares = vec![Square::A1, Square::D4, Square::H8];
+
+        assert_eq!(
+            squares.into_iter().collect::<Bitmask>(),
+            Bitmask::EMPTY
+                .with(Square::A1)
+                .with(Square::D4)
+                .with(Square::H8)
+        );
+        assert_eq!(
+            Vec::<Square>::new().into_iter().collect::<Bitmask>(),
+            Bitmask::EMPTY
+        );
+    }
+
+    #[test]
+    fn bitmask_extend() {
+        let mut mask = Square::A1.mask();
+        mask.extend(vec![Square::B2, Square::A1]);
+
+        assert_eq!(mask, Bitmask::EMPTY.with(Square::A1).with(Square::B2));
+
+        mask.extend(Vec::new());
+        assert_eq!(mask, Bitmask::EMPTY.with(Square::A1).with(Square::B2));
+    }
+
+    #[test]
+    fn bitmask_sub() {
+        assert_eq!(
+            Bitmask::from(0b00001101) - Bitmask::from(0b00110001),
+            Bitmask::from(0b00001100)
+        );
+        assert_eq!(
+            Bitmask::from(0b10101011) - Bitmask::from(0b01010101),
+            Bitmask::from(0b10101010)
+        );
+        assert_eq!(
+            Bitmask::from(0b01000001) - Bitmask::from(0b11111111),
+            Bitmask::from(0b00000000)
+        );
+    }
+
+    #[test]
+    fn bitmask_sub_assign() {
+        let mut mask = Bitmask::from(0b00001101);
+        mask -= Bitmask::from(0b00110001);
+
+        assert_eq!(mask, Bitmask::from(0b00001100));
+    }
+
+    #[test]
+    fn bitmask_mirror_vertical() {
+        assert_eq!(Square::A1.mask().mirror_vertical(), Square::A8.mask());
+        assert_eq!(Square::E4.mask().mirror_vertical(), Square::E5.mask());
+        assert_eq!(Bitmask::RANK2.mirror_vertical(), Bitmask::RANK7);
+        assert_eq!(Bitmask::FILEC.mirror_vertical(), Bitmask::FILEC);
+    }
+
+    #[test]
+    fn bitmask_mirror_horizontal() {
+        assert_eq!(Square::A1.mask().mirror_horizontal(), Square::H1.mask());
+        assert_eq!(Square::E4.mask().mirror_horizontal(), Square::D4.mask());
+        assert_eq!(Bitmask::FILEB.mirror_horizontal(), Bitmask::FILEG);
+        assert_eq!(Bitmask::RANK3.mirror_horizontal(), Bitmask::RANK3);
+    }
+
+    #[test]
+    fn bitmask_with_shared_orthogonal() {
+        assert_eq!(
+            Bitmask::EMPTY.with_shared(Square::A1, Square::A5),
+            Bitmask::FILEA
+        );
+        assert_eq!(
+            Bitmask::EMPTY.with_shared(Square::C4, Square::G4),
+            Bitmask::RANK4
+        );
+    }
+
+    #[test]
+    fn bitmask_with_shared_diagonal() {
+        let a1_h8 = Bitmask(0x8040201008040201);
+
+        assert_eq!(Bitmask::EMPTY.with_shared(Square::A1, Square::C3), a1_h8);
+        assert_eq!(Bitmask::EMPTY.with_shared(Square::F6, Square::D4), a1_h8);
+        assert_eq!(
+            Bitmask::EMPTY.with_shared(Square::C1, Square::E3),
+            [
+                Square::C1,
+                Square::D2,
+                Square::E3,
+                Square::F4,
+                Square::G5,
+                Square::H6
+            ]
+            .into_iter()
+            .collect()
+        );
+    }
+
+    #[test]
+    fn bitmask_with_shared_anti_diagonal() {
+        let a8_h1 = Bitmask(0x0102040810204080);
+
+        assert_eq!(Bitmask::EMPTY.with_shared(Square::A8, Square::D5), a8_h1);
+        assert_eq!(Bitmask::EMPTY.with_shared(Square::G2, Square::B7), a8_h1);
+        assert_eq!(
+            Square::H8.mask().with_shared(Square::B1, Square::A2),
+            Bitmask::EMPTY
+                .with(Square::A2)
+                .with(Square::B1)
+                .with(Square::H8)
+        );
+    }
+
+    #[test]
+    fn bitmask_with_shared_none() {
+        assert_eq!(
+            Bitmask::RANK1.with_shared(Square::A1, Square::B3),
+            Bitmask::RANK1
+        );
+    }
+
+    #[test]
+    fn bitmask_iter_len() {
+        let mask = Bitmask::RANK1.with(Square::E4).with(Square::H8);
+        let mut iter = mask.into_iter();
+
+        assert_eq!(iter.len(), 10);
+        iter.next();
+        assert_eq!(iter.len(), 9);
+        iter.next_back();
+        assert_eq!(iter.len(), 8);
+
+        let squares = iter.collect::<Vec<Square>>();
+        assert_eq!(squares.len(), 8);
+
+        let mut iter = Bitmask::EMPTY.into_iter();
+        assert_eq!(iter.len(), 0);
+        iter.next();
+        assert_eq!(iter.len(), 0);
+    }
+
+    #[test]
+    fn bitmask_is_subset() {
+        let full = Bitmask(u64::MAX);
+
+        assert!(Bitmask::EMPTY.is_subset(Bitmask::EMPTY));
+        assert!(Bitmask::EMPTY.is_subset(Bitmask::RANK1));
+        assert!(Bitmask::EMPTY.is_subset(full));
+        assert!(Bitmask::RANK1.is_subset(full));
+        assert!(Bitmask::RANK1.is_subset(Bitmask::RANK1));
+        assert!(Square::A1.mask().is_subset(Bitmask::FILEA));
+        assert!(!Bitmask::RANK1.is_subset(Bitmask::FILEA));
+        assert!(!full.is_subset(Bitmask::RANK1));
+    }
+
+    #[test]
+    fn bitmask_contains_all() {
+        let full = Bitmask(u64::MAX);
+
+        assert!(full.contains_all(Bitmask::RANK1));
+        assert!(Bitmask::RANK1.contains_all(Bitmask::EMPTY));
+        assert!(Bitmask::FILEA.contains_all(Square::A1.mask()));
+        assert!(!Bitmask::RANK1.contains_all(full));
+        assert!(!Bitmask::FILEA.contains_all(Bitmask::RANK1));
+    }
+
     #[test]
     fn bitmask_flip() {
         assert_eq!(
@@ -4563,14 +4563,14 @@ pub const QUEEN: [u64; 64] = [
 ];
 
 pub const WHITE_PAWN_ATTACKS: [u64; 64] = [
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
+    0x200,
+    0x500,
+    0xa00,
+    0x1400,
+    0x2800,
+    0x5000,
+    0xa000,
+    0x4000,
     0x20000,
     0x50000,
     0xa0000,
@@ -4686,14 +4686,14 @@ pub const BLACK_PAWN_ATTACKS: [u64; 64] = [
     0x500000000000,
     0xa00000000000,
     0x400000000000,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
+    0x2000000000000,
+    0x5000000000000,
+    0xa000000000000,
+    0x14000000000000,
+    0x28000000000000,
+    0x50000000000000,
+    0xa0000000000000,
+    0x40000000000000,
 ];
 
 pub const WHITE_PAWN_MOVES: [u64; 64] = [
@@ -4829,3 +4829,1026 @@ pub const BLACK_PAWN_MOVES: [u64; 64] = [
     0,
     0,
 ];
+
+/// The full a1-h8 style diagonal through each square, including the square itself.
+pub const DIAGONAL: [u64; 64] = [
+    0x8040201008040201,
+    0x80402010080402,
+    0x804020100804,
+    0x8040201008,
+    0x80402010,
+    0x804020,
+    0x8040,
+    0x80,
+    0x4020100804020100,
+    0x8040201008040201,
+    0x80402010080402,
+    0x804020100804,
+    0x8040201008,
+    0x80402010,
+    0x804020,
+    0x8040,
+    0x2010080402010000,
+    0x4020100804020100,
+    0x8040201008040201,
+    0x80402010080402,
+    0x804020100804,
+    0x8040201008,
+    0x80402010,
+    0x804020,
+    0x1008040201000000,
+    0x2010080402010000,
+    0x4020100804020100,
+    0x8040201008040201,
+    0x80402010080402,
+    0x804020100804,
+    0x8040201008,
+    0x80402010,
+    0x804020100000000,
+    0x1008040201000000,
+    0x2010080402010000,
+    0x4020100804020100,
+    0x8040201008040201,
+    0x80402010080402,
+    0x804020100804,
+    0x8040201008,
+    0x402010000000000,
+    0x804020100000000,
+    0x1008040201000000,
+    0x2010080402010000,
+    0x4020100804020100,
+    0x8040201008040201,
+    0x80402010080402,
+    0x804020100804,
+    0x201000000000000,
+    0x402010000000000,
+    0x804020100000000,
+    0x1008040201000000,
+    0x2010080402010000,
+    0x4020100804020100,
+    0x8040201008040201,
+    0x80402010080402,
+    0x100000000000000,
+    0x201000000000000,
+    0x402010000000000,
+    0x804020100000000,
+    0x1008040201000000,
+    0x2010080402010000,
+    0x4020100804020100,
+    0x8040201008040201,
+];
+
+/// The full a8-h1 style anti-diagonal through each square, including the square itself.
+pub const ANTIDIAGONAL: [u64; 64] = [
+    0x1,
+    0x102,
+    0x10204,
+    0x1020408,
+    0x102040810,
+    0x10204081020,
+    0x1020408102040,
+    0x102040810204080,
+    0x102,
+    0x10204,
+    0x1020408,
+    0x102040810,
+    0x10204081020,
+    0x1020408102040,
+    0x102040810204080,
+    0x204081020408000,
+    0x10204,
+    0x1020408,
+    0x102040810,
+    0x10204081020,
+    0x1020408102040,
+    0x102040810204080,
+    0x204081020408000,
+    0x408102040800000,
+    0x1020408,
+    0x102040810,
+    0x10204081020,
+    0x1020408102040,
+    0x102040810204080,
+    0x204081020408000,
+    0x408102040800000,
+    0x810204080000000,
+    0x102040810,
+    0x10204081020,
+    0x1020408102040,
+    0x102040810204080,
+    0x204081020408000,
+    0x408102040800000,
+    0x810204080000000,
+    0x1020408000000000,
+    0x10204081020,
+    0x1020408102040,
+    0x102040810204080,
+    0x204081020408000,
+    0x408102040800000,
+    0x810204080000000,
+    0x1020408000000000,
+    0x2040800000000000,
+    0x1020408102040,
+    0x102040810204080,
+    0x204081020408000,
+    0x408102040800000,
+    0x810204080000000,
+    0x1020408000000000,
+    0x2040800000000000,
+    0x4080000000000000,
+    0x102040810204080,
+    0x204081020408000,
+    0x408102040800000,
+    0x810204080000000,
+    0x1020408000000000,
+    0x2040800000000000,
+    0x4080000000000000,
+    0x8000000000000000,
+];
+
+/// Random keys for Zobrist hashing, one per square for each piece of each color,
+/// indexed by 'color as usize * 6 + piece.index()', then by square. The keys were
+/// generated with SplitMix64 from a fixed seed so hashes are stable across builds.
+pub const ZOBRIST_PIECES: [[u64; 64]; 12] = [
+    [
+        0x336d70086993e8ea,
+        0xfe7459908c8fec79,
+        0xb6107530d358ccbb,
+        0xa1e7566d75465fd1,
+        0x59453da1d35e7600,
+        0x2fa37fb0934eacf0,
+        0xa2e4de9722b18303,
+        0x3a43a1e2e46dd61,
+        0x52fd110ce2ad2110,
+        0x296e0fba635c6d46,
+        0x63c436ace8e6a66a,
+        0xf7adf1f5c41b7677,
+        0xf2efa060818245d3,
+        0x1106ddf23da92321,
+        0x6b2de44b00ace94a,
+        0x388572910f841e9c,
+        0x502ef3aa12eb9365,
+        0x93f69eb222e471ee,
+        0x3b330af0adc2b610,
+        0x295a72a0ebcd0f79,
+        0xc6e17ea1d37dc6d0,
+        0x6e69fb8d20fd127,
+        0x22fce6fbd7fc3d8,
+        0x34eda6631c088b25,
+        0xaf678489ff6c6beb,
+        0xdc3c9afe6880ed1d,
+        0xbf694a448fad930,
+        0x9578506405680a25,
+        0xb49b95dc86826c92,
+        0x18fbdb3c7ad3455a,
+        0xbced1b91a4612e59,
+        0x236985c45da335a7,
+        0x93b74a374fc878f1,
+        0xd7ebd13a4f6de61a,
+        0x95a7dea0809597f,
+        0xfcd82f76e49de004,
+        0x2e2902233cbd7a5c,
+        0xb3e51a1c361a5325,
+        0x478ced9c8c61db55,
+        0x78969f2048b82803,
+        0xa8b3918b33b4c52a,
+        0x2a22ca10991f87bd,
+        0xa121e2171a9a1668,
+        0xa0b9e2c848b4709d,
+        0x16fa4d4188f4b6a0,
+        0x30a3b143f638dc51,
+        0xb91d3c1bb7116ad0,
+        0x5693445ab76868dc,
+        0xb693e38d19445260,
+        0xb90dda5f74b1b1c7,
+        0x4c4e85af70cf3038,
+        0x5fa65b3a648b05f7,
+        0x75f853eb4e04f6a6,
+        0x3a2980730cdb968d,
+        0xd56bc4544989edc7,
+        0x13d5e6d3fb205398,
+        0x89abc1bbc09787a5,
+        0xdab59121e3cf4c3c,
+        0xd59261b0f77e8ca4,
+        0x97b2724d01a9dfd6,
+        0x9f80b64eabea704a,
+        0xf7fa0cd743569bd2,
+        0x1c6abca8eee32742,
+        0xe76dc98636c13d9f,
+    ],
+    [
+        0x357ced436cdc5703,
+        0x312a37b23eca07fb,
+        0xa934eca3c8d663a5,
+        0x50e66604b2565ecb,
+        0x3204fec5ad1c9a91,
+        0x382baedf85f42530,
+        0xf31ee89a43033489,
+        0xb7031337e83f5fe4,
+        0xd7666bc5c9b414b4,
+        0xe875159c6dcd02c9,
+        0xb5e1547aed573f05,
+        0x85713398389ced3f,
+        0xd606d4e63056bfe5,
+        0x94bdd539cce326e9,
+        0x5354984672290e14,
+        0x21959141152858bc,
+        0x89b0e1abce325249,
+        0x46f938691890adda,
+        0x109bea33fe805e35,
+        0x55e1b33b343d9f52,
+        0xa488fe6afd8db0f9,
+        0x17a1f15f1b7b4099,
+        0xf7625031f8abe248,
+        0x14819868303a52ec,
+        0x971cc367cba8eaa7,
+        0x452c8c51f5d34f53,
+        0xc1bc41e53e17a84a,
+        0x9194a056408ca645,
+        0x9ccd2c2a8f81f2f6,
+        0xefe65c81730ece97,
+        0x78acab9aa505c38f,
+        0xc0dfd74e63cddc5c,
+        0x6be524bf05069ec3,
+        0xf42ff04f297c5b18,
+        0xe6109d7f5cc2bea3,
+        0xff0439ad56238ba7,
+        0xb59e9ad75a539be0,
+        0xc7215a69c10589bb,
+        0xb89625ba607dff88,
+        0x35772ab9b09e4fac,
+        0x71f1430670412e26,
+        0x7c11e699867ff9c2,
+        0xced88b2b2ba3e541,
+        0xec3a1623ee828c5a,
+        0x5be89a40635a40d0,
+        0x36fa059a3eb5b7c0,
+        0xeb9804dfaf9dfdbd,
+        0x30edf0964684816,
+        0xb2244744217a4a5b,
+        0x8dda9d31583274a6,
+        0x3b49fec491f79648,
+        0x3aece1d3ea7b89f8,
+        0x58dae4fe29e90a4d,
+        0xfa93dea76f61ae4b,
+        0x8cbc781743cf17a8,
+        0x62ecbd4f3ac0859a,
+        0x1f773758d4d72d4a,
+        0x8f30b34c24a243d6,
+        0x365ebd8eeb6b25d,
+        0x57ef91cc27338471,
+        0x3c24460a09879b7d,
+        0x76f314b6ee85aaea,
+        0xd7b392ffa8971ac6,
+        0x33d5845b6cb5f884,
+    ],
+    [
+        0xffd9d77dfaaf5b8e,
+        0xac88f2644429b2cf,
+        0x2915d10e664524e,
+        0xa0260ec162028922,
+        0xb47a1430d329eac0,
+        0xa624a40e00a433dc,
+        0x89d7f917c9e14b0,
+        0x89d04c4c71788a38,
+        0x5edee97eaaf89f08,
+        0xdbc267d1a8ad3cf8,
+        0x6a5b44baab18ecf0,
+        0x5dab1c2e523aa8c0,
+        0x69a13fbafc45f0b0,
+        0x679b2c37ddd39fbf,
+        0x7985d275cfea918f,
+        0x4c31412ae3586649,
+        0x4168f2e11de7800a,
+        0xa5305cec2629be93,
+        0x6879e8537fc4dc23,
+        0x747d28e38e404ac6,
+        0x2c034912cab280a6,
+        0x9040a1b06f40749a,
+        0xa3b5d0e409f15c7a,
+        0xd11381129a4e9e70,
+        0x892a0debc32b78dd,
+        0x3ee321268b639108,
+        0x865a9574dfdb460d,
+        0x753865ead7348c68,
+        0x474e3faa970eb51e,
+        0x1ed3ffab1a698076,
+        0x955de7ea535662d6,
+        0x61dff7aa045922c5,
+        0xddc1489aebc588a2,
+        0x934a53e0121856c,
+        0xd31c471a69ccf02d,
+        0x4009050fb5468373,
+        0xc7bed9fd8ea24a14,
+        0x8c48bf69679f8b3f,
+        0xdccde812078290f5,
+        0xf166c3f64a28084f,
+        0xb37ab523543a2e9f,
+        0x7ef9f4a58d693ec7,
+        0x1c8277ec8eeda200,
+        0xcaaba150cbf98e2a,
+        0x4c8f9b4cd8e3791d,
+        0x39104b5562d453c2,
+        0xf9b2ea0b0bed4108,
+        0xa4b3c3667ecaaafa,
+        0x61b70161a33a2ac3,
+        0x9d5c7fcfcdbd7833,
+        0xb9168cca82a3c2e9,
+        0xeecad968f33d588b,
+        0x53dec2be85c76215,
+        0xdafc23b0d79d7039,
+        0x66255e741e43f8c3,
+        0xf9759bd190dcfadf,
+        0x770006edaa7b46ef,
+        0x5c107524c51be9f8,
+        0xb986ce049e80d736,
+        0x5c1c873249b28b67,
+        0x10955657b3435f00,
+        0xc536a970ba121074,
+        0x363cc769a580dfe0,
+        0x3b166fddec6d848a,
+    ],
+    [
+        0xad7fe85131b79ed5,
+        0x21d60ca3ccd14e19,
+        0x4144c5207e730c83,
+        0x1c7b8a90d9a155ae,
+        0x2159642a8ef91e79,
+        0x758913e253b3c37f,
+        0xab132d4e4d96d407,
+        0x8068a9d4df439967,
+        0xcf320a6d540f1bcf,
+        0x87f50cfd14b9c807,
+        0x81507d281a2466b4,
+        0xeb3e70580e30eb5d,
+        0xb82e20b4badce800,
+        0x7920996e56146783,
+        0xe767e560dfa1670f,
+        0xefdefc2b3f7c137b,
+        0x360fcce3c968b9b7,
+        0x8420b902ec7c8b79,
+        0x5df62751d421c5de,
+        0x4e9044001a51de3b,
+        0xfb06e15372dfab69,
+        0x4804aabbb91dc5dd,
+        0x3e6e3bb276a42f7b,
+        0x89bb7fbc91e2062b,
+        0x11bc76349c57d923,
+        0xe8213da471cfa296,
+        0xd0ad1c4b6e417a4f,
+        0x78facc7e30d25fb6,
+        0xb7fa3c55ba40725e,
+        0xd152f63803c0714,
+        0x14a0222ab2e9833a,
+        0x9d0a12e6dca9d2c9,
+        0x178b9093d1120560,
+        0xab503ecbbdb5fb43,
+        0xa2540674e4f27c8d,
+        0xac52fa98fd1beba0,
+        0xbb805e552569371b,
+        0x8f213f397150f537,
+        0x7f5a2b45d7708139,
+        0xf0ddb871401bd56c,
+        0x1d1519c392dc5d0b,
+        0x3378a78d75039a88,
+        0x90d598e135bb408d,
+        0x608e030c651a7ad9,
+        0x98ff4627b35f657f,
+        0x5f4007b951346aa5,
+        0x1a8d2389a173303d,
+        0xc7dbdbd7879087e0,
+        0xed58a56811a344c4,
+        0x941ad3607fbc8951,
+        0x59c2d513764eab6d,
+        0x378517dc8b49dbbd,
+        0xafd7273589864591,
+        0x1481d65da9e04722,
+        0xe09f79f5a47db3b8,
+        0xa8e153339f716ede,
+        0x3d953894da287aac,
+        0xa8031f83412e8ee,
+        0x461aaf7d1c2e602,
+        0xdd158fa7d2f9d090,
+        0x28cbea1520106e94,
+        0xab0886c3cfbbb62e,
+        0xfb95abd593933e86,
+        0x13d142fdd2c7db43,
+    ],
+    [
+        0x757aaaf729b4a955,
+        0xfeb312d5f4a2b1b8,
+        0x3f2ddb07dbb0aa7b,
+        0xd825be12f244e3b9,
+        0xaf68229c2a9d83ea,
+        0x3226094223bc9e6c,
+        0x7e3f8050277314c3,
+        0xbec54fec98b03e42,
+        0xd43831959dd1809,
+        0x7eb4246ec6505d5c,
+        0xaea70ac51d06ab53,
+        0xf2cfb3b25e475c01,
+        0x698efe4cf9d050f6,
+        0xc6b70f2cfa1ad74f,
+        0xbf3ced239903fd5e,
+        0xf8e9f86c2c50a885,
+        0x557680ee53662161,
+        0xd51791ec7333707,
+        0xc341798fdb22b56a,
+        0x31a0441e4e6f45b4,
+        0x45ecea9773254e2f,
+        0x8e1550dbcbadea0b,
+        0x36ca7a99c2639322,
+        0x3bf76f6c12398a87,
+        0x65c5cb5bd5ba61f6,
+        0xdf3a7e97d308aab0,
+        0x133e804b6b7fedd4,
+        0xda713ec1b3b235ea,
+        0xef8da00a9f0b71fa,
+        0xd82a070750779906,
+        0xb120a6bb5692642a,
+        0xa77b5e7adfb008b9,
+        0x3cda63cf023697d9,
+        0x5eee70e70e9dca7a,
+        0xef762357d6d70c61,
+        0xad68599a1c7dc92c,
+        0x75daa7a3c47d38a8,
+        0x7ebafbbed7669454,
+        0x6a80a30d7bdfa463,
+        0x7cde0ee2b1cc0394,
+        0x1fbbc5bbe0cc1293,
+        0x58e657f4e0b8d7d4,
+        0x1e714f725f1332df,
+        0x6bf9ed74c43bf480,
+        0x337a3bda32114097,
+        0x5d5556bfb43bff40,
+        0xc00c47939eca37e8,
+        0x4f8def3e4a3a8500,
+        0xd9a64a9693004156,
+        0x429f596320d782ed,
+        0xf7fe1b20752ff04c,
+        0xd7a25bc3a442c6fc,
+        0x425360d9420f4119,
+        0x4c8355de5043513d,
+        0xda8994101f52526,
+        0xa76229d06ba8ad5c,
+        0x5489124c359340b5,
+        0xc7fc8f9ae5a660b,
+        0x293d885e669f2c0b,
+        0x4ac45af19c94f004,
+        0x9f04ea2c603d182c,
+        0x639a1f19e8a4914,
+        0x83bd05789012da94,
+        0x47e8e223270c18f3,
+    ],
+    [
+        0x618779eac1f526ae,
+        0x51e049de24a89520,
+        0xd4cd6f95f37decd3,
+        0xb985bc3254dc8a81,
+        0x55b6eb5bc91fc5c,
+        0x46388a6a4367339e,
+        0x1737aa81422bd003,
+        0x8f22a36d046adaab,
+        0x46bf16f5c7cacc5d,
+        0xf351c4f1e44187e7,
+        0x5b3c88a1362a2e78,
+        0xaf0e058fb65502b8,
+        0xe15cc57bfc3ac56a,
+        0x264972bde0f986ae,
+        0xb9748578beb0bcc9,
+        0x47c446547412a8f9,
+        0xe59430cf8c6c0753,
+        0x34aef4387a24142b,
+        0x983d0cadb6201949,
+        0x42b988191d185861,
+        0x6862284a1127822d,
+        0x19110e5fa2f87c27,
+        0x9cc0cd40f652e939,
+        0x1514729859de501f,
+        0xa0cd303fe7c00ba3,
+        0x21c164ba3f53f23d,
+        0x5ec70b89ffcf2d8f,
+        0x4b1f6f7bd19c4192,
+        0xf9eb4ec05456db95,
+        0xe120f083e3953add,
+        0x8578b37120eac39b,
+        0xaa6d0a9a609e8d82,
+        0x6984a8706acf37d3,
+        0x929c80e70fcd1a11,
+        0x330c693d1fb0fd04,
+        0xeea5897d09e26b10,
+        0x464f438a2bd0ce47,
+        0x3ba729054fba93e2,
+        0x90a306336578b4ff,
+        0x9860beb0eac1568,
+        0x8b8e2f1f1fe2bd6,
+        0x2164492f66d7ed00,
+        0x9991917865bf5c83,
+        0xb13505d0bcb1766c,
+        0xa10b3a889185595f,
+        0xeddb8bf51a8c2a17,
+        0x20ebf05be1a1ccad,
+        0xa5b58b521f32fdfc,
+        0x9fb23936cc757d0a,
+        0x5fbf4e7b25c391f2,
+        0xba056b948e6aed97,
+        0xf500130b081e803c,
+        0x377f409da568c915,
+        0x41201a6ae0e51c90,
+        0x89c7425fdf69c395,
+        0x9c5cb6d43b4b8240,
+        0xd67efe098888bf11,
+        0x45276468a91f5a8b,
+        0x923ebe0bf2d62cef,
+        0x14f0e65b9cacbd2f,
+        0x946e5c9706e7e466,
+        0x3a3a34ce0d69d138,
+        0x439818bf61cddc79,
+        0x5515fb548422591f,
+    ],
+    [
+        0xec6157b107eb116,
+        0xb9fc1fd4d7473606,
+        0x414956f5f8d53209,
+        0xb5300871292d3a7,
+        0x48a093f6d8c284b3,
+        0xf565bb79ea296e3e,
+        0x5a2de0ed8a38142b,
+        0xc326029d62ec3feb,
+        0x716bfda2931a3589,
+        0x5e76c04c6d97295a,
+        0xdacdab41f808f1b2,
+        0xac57438827b5a088,
+        0x125d4cc13efd4102,
+        0x9cacaa2ff06a929e,
+        0x2eb86339e5c80ac,
+        0xbe0ae182286d5675,
+        0x5218e5d4d85c475d,
+        0xcc239629ce139501,
+        0x1605a52041e72820,
+        0x896f9ff700f2877c,
+        0x6aa4c83be06c0c7d,
+        0xff979ae4769cfa4c,
+        0x16698c10697c5b8a,
+        0xa6b7f8487e8ae771,
+        0x848fd79ae63d1b74,
+        0x65bc9ef8cd7c732a,
+        0xd4f5f075a8879ed,
+        0x5c1c5c75b11b0902,
+        0x3c4f64890ca6faf0,
+        0x2af73eef81f0cef6,
+        0x4b176703f6ece2d4,
+        0xec2fe5661c1f0d31,
+        0xe2ae1ea8f8a4180b,
+        0x6be4e27474a5ecd3,
+        0x781db7780338a62b,
+        0x95b38993a18f6ca0,
+        0x41793b092edefecc,
+        0x86e3e9a3837c8bf1,
+        0x2247e7ec16b97a66,
+        0xf5348b4ec7ef9ce0,
+        0xe202c3d2c8a72d87,
+        0x13fc414c8b5856e4,
+        0x8d13f3aa8463d10e,
+        0x742beca159010bea,
+        0x7068390525c24c32,
+        0xc9286754ff185f1e,
+        0xfc054d8cca45ef5a,
+        0xb3c37c7b2466e68c,
+        0x208bca7d479e5237,
+        0x31f6eb53915c6480,
+        0x60cb2c3058961514,
+        0x73378c1664b191ae,
+        0xf4a2cef33b856321,
+        0xf3cee8738f322f1a,
+        0x9e618f7e7e9ce507,
+        0x8e0ba9bce8e4486a,
+        0xfb0a96b234fe4613,
+        0xc0f1685e9da62176,
+        0xdd9b5ca99e2875dc,
+        0x79640bf97cc01a5c,
+        0xd46ea71bf7fa5c94,
+        0xc267a525c0c09ac6,
+        0xf723e6d2c338450d,
+        0x8f4d9f2d0ba22a46,
+    ],
+    [
+        0xa4106530d5def2f5,
+        0x30b84f08b6900edb,
+        0xb51fc8be6563d0dc,
+        0xfbbaab7710dae8c0,
+        0xda67e747abffec90,
+        0x90688054cc0d7642,
+        0xbb6ef1956f099341,
+        0x82af4923622608f4,
+        0xd08100507f6ac3b3,
+        0x2d28fd79465cfec1,
+        0xa8db0141f969cacb,
+        0x1234e6c547d96fa8,
+        0x7a2b025970cb162b,
+        0xc52aca70bbcae3c2,
+        0x830c8ef12c5854c3,
+        0x9634cd6888002017,
+        0x2609fabe229a07fe,
+        0x54ab29e707a15371,
+        0xd626bd362e7ed8ca,
+        0x7764e5067790c68b,
+        0xdfc3d58068b084a,
+        0xe0f2d85967c24a2d,
+        0x20fccae328c063cb,
+        0x683abd140614c4d8,
+        0xe9539c50d5de52ba,
+        0x5fe7726e0fbb96bc,
+        0xf369b347786cc72f,
+        0x33d4fc1b5af7bdfe,
+        0x62452158298dff72,
+        0x85a2ef004ba6bc4f,
+        0xe04de0752da68313,
+        0xa3613b67ea9e19ce,
+        0x8e878aae6a0d7af,
+        0x3dda20c1ee88910d,
+        0xc67d4817bf5073f9,
+        0x2bfccfaacc054882,
+        0x36fd299d7f04050d,
+        0xe5641a99e17e21ae,
+        0xedc87f550d7fcf42,
+        0x3a49ae534be3e297,
+        0xa5d2c16a024636dd,
+        0x5391fc990ca5283e,
+        0x1b1f5d3655fe7621,
+        0x86c24fa03cd523f,
+        0xc0750f56da7b2704,
+        0x60ac5ba8cea41758,
+        0x8c3ddcb8f55afd7d,
+        0x58cc3080cb60287b,
+        0x6d51b265ec53f8ce,
+        0x742185d4121cb52d,
+        0x46cbd372d2a2b4af,
+        0xc4e73592bbaac3ff,
+        0x42c794e9bcd3dafc,
+        0x3817b1429f1a3353,
+        0x286a2d3c8a9a889f,
+        0x67dc946f8cf4f965,
+        0x2448b3f2969c2e48,
+        0xe039f329d0ccdfda,
+        0x8def6dc91039b035,
+        0x13f27c7ded4857ee,
+        0x860ec65c24926002,
+        0x7f849f2c7ff8d0bb,
+        0xc83c90b933cdce40,
+        0x30e4a4b33ebdb998,
+    ],
+    [
+        0x8a0373e45e3688ea,
+        0xb23dfa297fc9e5f1,
+        0x38096501270e044e,
+        0x5bf56ec2cfec2995,
+        0x7e0dc3ea82f0043e,
+        0x419b13f29991dde,
+        0x6b7f35c1d1363286,
+        0xdcde1d664462282b,
+        0x5bbdf157f5a7e3a9,
+        0x4055e3c9acd2d59a,
+        0xe82301c66d6f7f71,
+        0xbf413f3ae51de2c3,
+        0x3ab3281e7d24fd3b,
+        0x6898847d77d9063a,
+        0x6524b78c4ac042bb,
+        0x4725cc6aea019b77,
+        0x9114eeba2f188982,
+        0x51661e6d7e8a556b,
+        0x30ed87037e60a40b,
+        0xd3d34d7e82b32e00,
+        0x8fde78e5a3295240,
+        0xb787aa3fad3b0c12,
+        0x2b50ec331df8f38c,
+        0x7aad9d52c985488e,
+        0x75a886c493e572f0,
+        0xb6d46104d53c7479,
+        0x54a68d27ce3750e4,
+        0x194ce87ec9dfd4f2,
+        0x748ba7feaa109da1,
+        0x94603f0aa62e6b80,
+        0x7d5409d45fac2d03,
+        0x2161ae753aa16db4,
+        0x38455f8fa970c15,
+        0xf1790323a69b8a2f,
+        0xfd7fc55777e457af,
+        0xb6c379d53ccba6ae,
+        0x58b8263b29769d2a,
+        0xf3cd3aa97ba57e2f,
+        0x2d743af2993f34d5,
+        0xc99b76c22718558f,
+        0x12528831e563b0c0,
+        0xd2415195fac058a8,
+        0x65a91ec61042ce15,
+        0x9563d1ec8a3473c0,
+        0x7f1052e24904f902,
+        0x156af9bb641e60cc,
+        0x63a7f31c2e6fefb8,
+        0xcc54d51b5fe89d6c,
+        0xe6362e04dff4eff4,
+        0xb59dbf7b6b1416e4,
+        0x448b2dc79b433bea,
+        0xd23512241154aa6d,
+        0xe512d40dad0d3e6c,
+        0x928fc9c8b4948388,
+        0xf8f4800dcf83ca96,
+        0xeaa9993b71afee0a,
+        0xa44d49353c454543,
+        0xdb603762cf5bddc6,
+        0x710da5846b3ce357,
+        0x6be05215a4195aec,
+        0x97d73a02d7f1cb3,
+        0x1e56890285199152,
+        0xc28f1318bd47d96a,
+        0xa9fc08bc2d87a401,
+    ],
+    [
+        0x6f874eea85e7a36d,
+        0x8182bfb7a31ad8a8,
+        0x8ccc98451a57f75f,
+        0xb2a7438160e5ff80,
+        0xc6225aa32151c1c2,
+        0x4d5f6786eed2c0ca,
+        0x3946cc51419f2941,
+        0xd3c13d7b2b0f5ea6,
+        0xa6a03f64a58b709a,
+        0x4633b42a386545b8,
+        0x21b1b01adbad129b,
+        0xe2c47b05f812f9f5,
+        0x4c80b31e344cdc4a,
+        0xe3dcff4b9e949dcf,
+        0x2729708d3af46778,
+        0x98b496f095a60284,
+        0x58acefc7592f1590,
+        0x7844ed9647b975ad,
+        0x5c954384552f8ba7,
+        0xb1d071e552b1eb84,
+        0x8e88a68c81946c35,
+        0x3aa45fe2560371a0,
+        0x5c819dab36c8e1d,
+        0x5756a7e544ad09ac,
+        0x76277ac34f1d61b0,
+        0x4cf3236a05bd3e10,
+        0x363841bebb89a072,
+        0x199c3768b7410905,
+        0x67053d79d6c6d03c,
+        0xcf54a83dbddf4ef,
+        0xc3248d94bb882bd0,
+        0x8aad2045ad35987c,
+        0x861434b9750bfa6c,
+        0x70aec7c21c74c44b,
+        0x695fabb9c332568f,
+        0xefb0563e55f06551,
+        0xee3f544b589b8432,
+        0x9c3a9c678a37d376,
+        0x1bb3f7e3ca2e2924,
+        0x4ecd45fa7804fb68,
+        0xb7236d08ef9f865d,
+        0x5a09eaaa364fa6de,
+        0xc8d147d3b8c3948c,
+        0x4d6a8db5c9aff76d,
+        0xb344acbebe160bb0,
+        0xf70afd773ff658ff,
+        0x2695a7d4936fe52a,
+        0xafeb36f2da1ab262,
+        0x34dc9e1966f1fdc8,
+        0xfdd16e0af249b9ba,
+        0xb940b6a293ab1bca,
+        0xcd74d813d076e577,
+        0x9253f4a066def80b,
+        0xe79a53e4c9643b6c,
+        0xf6156804f4656855,
+        0x7c8d913d88350b05,
+        0xf3214a1fd7491af7,
+        0x3fccd6f072c0a43a,
+        0x213b2b766ab2c91e,
+        0xee77f2920f995b8d,
+        0x86d4c59e2b6aa10f,
+        0xdfd3cbc729e51090,
+        0x6956ff54e456e611,
+        0xc45859225c3ed249,
+    ],
+    [
+        0x9b39599fd46147e6,
+        0x9a8a5c1047c8e08b,
+        0x7ef30ff07299d4d7,
+        0xa4c3ded9bb9e50f3,
+        0x2e3855e815908585,
+        0xba9f2fdb4129aa96,
+        0x4dde455c5081b879,
+        0x8da399ba5445a428,
+        0xac3049c2cc9c04ba,
+        0xeebe2f905c6f6830,
+        0xb4146eb9d8c844d7,
+        0x37d2cd21971d9044,
+        0x13a9e243bc1d124d,
+        0x40ef0d1e09a1c52a,
+        0x93dd671993ed7706,
+        0x7ceb33ff28d8438c,
+        0x260b02ecb663afb7,
+        0xca208e2e4869dcaf,
+        0xd06cfb3c16c18231,
+        0xf40b6fe0f9c3b7a2,
+        0x7a72dc1da64d08d5,
+        0x4de55f120e92c77d,
+        0x9d3b2c886c3020e5,
+        0x1c8cbf1f93f1b4b1,
+        0xf11593f3004e3b99,
+        0x52372ec3465baa,
+        0x41781735447499ca,
+        0xc610b99e40d49c0d,
+        0xc5973b620c09134f,
+        0xfa79a8f5e2adb690,
+        0x5424be3673b295de,
+        0x8fd8d5f1a51e4098,
+        0xddc289c922752126,
+        0xa71bae7f78b202b6,
+        0x4e3dd93ea03a862b,
+        0x588ae1b236bc71fb,
+        0x1b18cc7e175db13c,
+        0xf3a6c43ba053ef3c,
+        0x8837dedc4b397ea4,
+        0xaed411ceb7968dec,
+        0x278ff4fbc4cbb53d,
+        0x25a0e0184e52e631,
+        0x6b6859e50cce491f,
+        0xea8e446d05309a2,
+        0xac0be9648b62d4dc,
+        0x7fb4c34a671a8931,
+        0xe98be5462a35f380,
+        0xc77c3489a1de66ae,
+        0xd2d8b3f7242b274b,
+        0x822b2ee6e8e5daa7,
+        0x29fb9ca8770e3e54,
+        0xc7c242dca4ba9a86,
+        0xa18208b5c37b27a4,
+        0x3c7c86cc57354410,
+        0x234ac16b0f791a77,
+        0xe22d7243647351f5,
+        0xa76cd441592261f7,
+        0xcaeab6908f54a195,
+        0x1857c670e77546d5,
+        0x86704f26312926eb,
+        0x699de802f8581f18,
+        0xd73a51e66d5150,
+        0x43cbdf623bf15802,
+        0x6004ae892d0742f1,
+    ],
+    [
+        0x3e0d920525b4f58e,
+        0x9ddd3c50f8cf9a9f,
+        0x8f66a4a51b15c17a,
+        0xbe3125fbb7182a71,
+        0x605c6b1f7067860,
+        0x14af0f9a2fc90441,
+        0xeefe61f598a95920,
+        0x66df69a720f83cdc,
+        0xaf5fb94f55142f4e,
+        0x683723e89be2aa2f,
+        0x6cca8c3b34bc02d5,
+        0xd00cd1f88b76b972,
+        0xe792556eef049e36,
+        0x8efaca6b49e0d8d,
+        0x8a0ce0f1fec34283,
+        0xf27a7ed0781b0a18,
+        0xc26a21a001e4aaef,
+        0xa83fe9d86690cdfe,
+        0x10623746c97cd06e,
+        0xa3b918977ce3155,
+        0x8ea499ac4432300c,
+        0xd1e9244765c6a932,
+        0x3f7b3cd703adccff,
+        0xa5b9eb462ae3c222,
+        0xb77047deb2dec6f,
+        0x79d80a4f711cecf1,
+        0x4336abc422542564,
+        0xea26dffe79f45b6e,
+        0xb3192c05b8eff74d,
+        0x691fd307ec2f6d6c,
+        0xa4db9cf8b509917f,
+        0xf6d69f2d5eb9b8db,
+        0x80eb8d3006d48b16,
+        0x1efe3afb70977eb0,
+        0xa6c6eacd89eac8f2,
+        0x28ea6e3ec5c58656,
+        0x95abb75a3f0d0367,
+        0xdab6d97ceb03ffb0,
+        0x58a7f7e458121211,
+        0x63a5246cbbdfe973,
+        0xee469cf8de1b3720,
+        0x87ecad98315cc2bc,
+        0x680ccfdd8cae0d5a,
+        0xce0c1b616068a705,
+        0x45f1496ddecd7769,
+        0x327a358f76136ba8,
+        0x1cbe39964a2a476d,
+        0x7b4278068a9d4cd5,
+        0x3277a2a9945a3b26,
+        0xb6c2e6e7f50f1eaa,
+        0xfba6052b7c5a94d0,
+        0x9ed6db872bdab81f,
+        0x1738b01274b32195,
+        0x456dffe2196eb70d,
+        0x2a9e9f0e70c7864e,
+        0xf92a71aea69e5cb6,
+        0xf47becaeb13127b4,
+        0x78c185970e2b9211,
+        0x796ea601f4f7007f,
+        0x8ca1331893bdd289,
+        0xb980a5c27f6f7f7c,
+        0x484e47c7d9074ea0,
+        0x3556b35827651c6f,
+        0xd8b23607a7940295,
+    ],
+];
+
+/// Zobrist keys for castling rights, in the order white kingside,
+/// white queenside, black kingside, black queenside.
+pub const ZOBRIST_CASTLE: [u64; 4] = [
+    0x1090915cc3a22ad1,
+    0xae75d5c5b570ed13,
+    0x1ee21b96874c7b98,
+    0xe700f66724e976c8,
+];
+
+/// Zobrist keys for the file of the en passant square, indexed by file.
+pub const ZOBRIST_EN_PASSANT: [u64; 8] = [
+    0x6bd946f80009fee8,
+    0x9c680196b5eed1be,
+    0x16396b852413ffcb,
+    0x1e8cee0ce43764b5,
+    0xe097ca21c27e7fa4,
+    0xf789e35d78163666,
+    0x6078557d1a25d9eb,
+    0xe8e305b09adc94d0,
+];
+
+/// Zobrist key mixed into the hash when black is to move.
+pub const ZOBRIST_BLACK_TO_MOVE: u64 = 0xc5ef293e4d70aa29;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitmask::Bitmask;
+    use crate::square::Square;
+
+    #[test]
+    fn diagonal_corner() {
+        assert_eq!(DIAGONAL[Square::A1 as usize], 0x8040201008040201);
+        assert_eq!(DIAGONAL[Square::H8 as usize], 0x8040201008040201);
+        assert_eq!(DIAGONAL[Square::H1 as usize], Square::H1.mask().0);
+        assert_eq!(DIAGONAL[Square::A8 as usize], Square::A8.mask().0);
+    }
+
+    #[test]
+    fn pawn_attacks_back_rank() {
+        // the attack tables are also used in reverse, so a king on
+        // its back rank can find the pawns that attack it.
+        assert_eq!(
+            WHITE_PAWN_ATTACKS[Square::E1 as usize],
+            (Square::D2.mask() | Square::F2.mask()).0
+        );
+        assert_eq!(
+            BLACK_PAWN_ATTACKS[Square::E8 as usize],
+            (Square::D7.mask() | Square::F7.mask()).0
+        );
+        assert_eq!(WHITE_PAWN_ATTACKS[Square::H1 as usize], Square::G2.mask().0);
+        assert_eq!(BLACK_PAWN_ATTACKS[Square::A8 as usize], Square::B7.mask().0);
+    }
+
+    #[test]
+    fn antidiagonal_corner() {
+        assert_eq!(ANTIDIAGONAL[Square::A8 as usize], 0x102040810204080);
+        assert_eq!(ANTIDIAGONAL[Square::H1 as usize], 0x102040810204080);
+        assert_eq!(ANTIDIAGONAL[Square::A1 as usize], Square::A1.mask().0);
+        assert_eq!(ANTIDIAGONAL[Square::H8 as usize], Square::H8.mask().0);
+    }
+
+    #[test]
+    fn diagonals_center() {
+        // together, the diagonals through a square are the bishop moves and the square.
+        for square in [Square::D4, Square::E5, Square::C6] {
+            assert_eq!(
+                DIAGONAL[square as usize] | ANTIDIAGONAL[square as usize],
+                BISHOP[square as usize] | square.mask().0
+            );
+        }
+
+        assert_eq!(
+            Bitmask(ANTIDIAGONAL[Square::D4 as usize]),
+            [
+                Square::A7,
+                Square::B6,
+                Square::C5,
+                Square::D4,
+                Square::E3,
+                Square::F2,
+                Square::G1
+            ]
+            .into_iter()
+            .collect()
+        );
+    }
+}
@@ -9,23 +9,32 @@ pub struct CastleRights {
     kingside_file: File,
     /// The File the Queen-side rook starts on.
     queenside_file: File,
-    /// The turn kingside,queenside castle was lost for white.
+    /// The ply kingside,queenside castle was lost for white.
     /// A negative number indicates castling has not been lost.
-    white_lost: (i16, i16),
-    /// The turn kingside,queenside castle was lost for black.
+    white_lost: (i32, i32),
+    /// The ply kingside,queenside castle was lost for black.
     /// A negative number indicates castling has not been lost.
-    black_lost: (i16, i16),
+    black_lost: (i32, i32),
 }
 
 impl CastleRights {
+    /// The ply of the move played at the fullmove by the color, used to
+    /// record when castling was lost. A right can be lost on the opponents'
+    /// move, by capturing the rook, so the fullmove alone is not enough.
+    pub fn ply(fullmoves: u16, turn: Color) -> u32 {
+        fullmoves as u32 * 2 + (turn == Color::Black) as u32
+    }
+
     /// Whether the color has kingside castling at a given turn.
     pub fn has_kingside_castle(&self, color: Color, turn: u16) -> bool {
-        turn as i16 > self.rights(color).0
+        let lost = self.rights(color).0;
+        lost.is_negative() || Self::ply(turn, Color::White) < lost as u32
     }
 
     /// Whether the color has queenside castling at a given turn.
     pub fn has_queenside_castle(&self, color: Color, turn: u16) -> bool {
-        turn as i16 > self.rights(color).1
+        let lost = self.rights(color).1;
+        lost.is_negative() || Self::ply(turn, Color::White) < lost as u32
     }
 
     /// Whether the color has castling in the given direction at the given turn.
@@ -36,6 +45,17 @@ impl CastleRights {
         }
     }
 
+    /// The castle rights at the given turn as (white kingside, white queenside,
+    /// black kingside, black queenside).
+    pub fn as_flags(&self, turn: u16) -> (bool, bool, bool, bool) {
+        (
+            self.has_kingside_castle(Color::White, turn),
+            self.has_queenside_castle(Color::White, turn),
+            self.has_kingside_castle(Color::Black, turn),
+            self.has_queenside_castle(Color::Black, turn),
+        )
+    }
+
     /// The Square the kingside rook starts on, given a color.
     pub fn kingside_rook_square(&self, color: Color) -> Square {
         Square::new(self.kingside_file, color.back_rank())
@@ -140,7 +160,7 @@ impl CastleRights {
     /// castling through a piece, which is not allowed. This mask will not
     /// include the king square or rook square, since they won't block themselves.
     pub fn queenside_block_mask(&self, king: Square, color: Color) -> Bitmask {
-        let rook = self.kingside_rook_square(color);
+        let rook = self.queenside_rook_square(color);
         let (king_target, rook_target) = self.queenside_target_squares(color);
 
         // the resulting block mask is the squares between the king and its target and
@@ -193,34 +213,34 @@ impl CastleRights {
         }
     }
 
-    /// Inform the CastleRights that the color has lost kingside castle on the given turn.
+    /// Inform the CastleRights that the color has lost kingside castle on the given ply.
     /// If the color has already lost kingside castling, then no changes are made.
-    pub fn lose_kingside(&mut self, color: Color, turn: u16) {
+    pub fn lose_kingside(&mut self, color: Color, ply: u32) {
         if self.rights(color).0.is_negative() {
             match color {
-                Color::White => self.white_lost.0 = turn as i16,
-                Color::Black => self.black_lost.0 = turn as i16,
+                Color::White => self.white_lost.0 = ply as i32,
+                Color::Black => self.black_lost.0 = ply as i32,
             }
         }
     }
 
-    /// Inform the CastleRights that the color has lost queenside castle on the given turn.
+    /// Inform the CastleRights that the color has lost queenside castle on the given ply.
     /// If the color has already lost queenside castling, then no changes are made.
-    pub fn lose_queenside(&mut self, color: Color, turn: u16) {
+    pub fn lose_queenside(&mut self, color: Color, ply: u32) {
         if self.rights(color).1.is_negative() {
             match color {
-                Color::White => self.white_lost.1 = turn as i16,
-                Color::Black => self.black_lost.1 = turn as i16,
+                Color::White => self.white_lost.1 = ply as i32,
+                Color::Black => self.black_lost.1 = ply as i32,
             }
         }
     }
 
-    /// Inform the CastleRights that the color has lost castling on the given turn,
-    /// in the given direction, for the given color.
-    pub fn lose(&mut self, color: Color, side: CastleDir, turn: u16) {
+    /// Inform the CastleRights that the color has lost castling on the given ply,
+    /// in the given direction, for the given color. See 'CastleRights::ply'.
+    pub fn lose(&mut self, color: Color, side: CastleDir, ply: u32) {
         match side {
-            CastleDir::Long => self.lose_queenside(color, turn),
-            CastleDir::Short => self.lose_kingside(color, turn),
+            CastleDir::Long => self.lose_queenside(color, ply),
+            CastleDir::Short => self.lose_kingside(color, ply),
         }
     }
 
@@ -250,25 +270,23 @@ impl CastleRights {
         }
     }
 
-    /// Get what the castle rights were at the given fullmove index.
-    pub fn index(&self, fullmoves: u16) -> Self {
+    /// Get what the castle rights were in the position at the given fullmove, with
+    /// 'turn' up to move. Castling lost on a ply was still held in the position
+    /// the losing move was played from, whichever color played it.
+    pub fn index(&self, fullmoves: u16, turn: Color) -> Self {
         let mut white_rights = self.white_lost;
         let mut black_rights = self.black_lost;
-
-        if (fullmoves as i16) < white_rights.0 {
-            white_rights.0 = -1;
-        }
-
-        if (fullmoves as i16) < white_rights.1 {
-            white_rights.1 = -1;
-        }
-
-        if (fullmoves as i16) < black_rights.0 {
-            black_rights.0 = -1;
-        }
-
-        if (fullmoves as i16) < black_rights.1 {
-            black_rights.1 = -1;
+        let ply = Self::ply(fullmoves, turn) as i32;
+
+        for lost in [
+            &mut white_rights.0,
+            &mut white_rights.1,
+            &mut black_rights.0,
+            &mut black_rights.1,
+        ] {
+            if ply <= *lost {
+                *lost = -1;
+            }
         }
 
         Self {
@@ -280,7 +298,7 @@ impl CastleRights {
     }
 
     /// Get the castling rights for the color.
-    fn rights(&self, color: Color) -> (i16, i16) {
+    fn rights(&self, color: Color) -> (i32, i32) {
         match color {
             Color::White => self.white_lost,
             Color::Black => self.black_lost,
@@ -289,14 +307,14 @@ impl CastleRights {
 
     /// Creates a new CastleState object
     /// with the move castle was lost set
-    /// to i16::max, indicating castling
-    /// is lost in the start position.
+    /// to 0, indicating castling is lost
+    /// before the first move is played.
     pub fn none() -> Self {
         Self {
             kingside_file: File::H,
             queenside_file: File::A,
-            white_lost: (i16::MAX, i16::MAX),
-            black_lost: (i16::MAX, i16::MAX),
+            white_lost: (0, 0),
+            black_lost: (0, 0),
         }
     }
 
@@ -309,9 +327,10 @@ impl CastleRights {
         } else {
             let mut result = String::new();
 
-            for dir in [CastleDir::Short, CastleDir::Long] {
-                for color in [Color::White, Color::Black] {
-                    if self.has_castle(color, u16::MAX, dir) {
+            // the canonical order is K, Q, k, q.
+            for color in [Color::White, Color::Black] {
+                for dir in [CastleDir::Short, CastleDir::Long] {
+                    if !self.has_lost(color, dir) {
                         result.push(self.castle_dir_as_char(color, dir));
                     }
                 }
@@ -321,6 +340,14 @@ impl CastleRights {
         }
     }
 
+    /// Has the color lost castling in the given direction, at any turn?
+    pub fn has_lost(&self, color: Color, dir: CastleDir) -> bool {
+        match dir {
+            CastleDir::Long => self.rights(color).1 > -1,
+            CastleDir::Short => self.rights(color).0 > -1,
+        }
+    }
+
     /// Does the color have any castling rights?
     pub fn lost_all_castle(&self, color: Color) -> bool {
         match color {
@@ -371,3 +398,88 @@ impl CastleDir {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::FenParser;
+
+    #[test]
+    fn as_flags() {
+        assert_eq!(
+            CastleRights::default().as_flags(1),
+            (true, true, true, true)
+        );
+        assert_eq!(
+            CastleRights::none().as_flags(1),
+            (false, false, false, false)
+        );
+
+        let mut castle = CastleRights::none();
+        castle.give(Color::White, CastleDir::Short);
+        castle.give(Color::Black, CastleDir::Long);
+        assert_eq!(castle.as_flags(1), (true, false, false, true));
+    }
+
+    #[test]
+    fn has_castle() {
+        let castle = CastleRights::none();
+        assert!(!castle.has_kingside_castle(Color::White, 0));
+        assert!(!castle.has_queenside_castle(Color::Black, 1));
+
+        let mut castle = CastleRights::default();
+        assert!(castle.has_kingside_castle(Color::White, 1));
+        castle.lose(
+            Color::White,
+            CastleDir::Short,
+            CastleRights::ply(3, Color::White),
+        );
+        assert!(castle.has_kingside_castle(Color::White, 2));
+        assert!(!castle.has_kingside_castle(Color::White, 3));
+        assert!(castle.has_queenside_castle(Color::White, 3));
+    }
+
+    #[test]
+    fn to_fen_string() {
+        assert_eq!(CastleRights::default().to_fen_string(), "KQkq");
+        assert_eq!(CastleRights::none().to_fen_string(), "-");
+
+        let mut castle = CastleRights::default();
+        castle.lose(Color::Black, CastleDir::Short, 4);
+        castle.lose(Color::Black, CastleDir::Long, 4);
+        assert_eq!(castle.to_fen_string(), "KQ");
+
+        castle.lose(Color::White, CastleDir::Short, 6);
+        assert_eq!(castle.to_fen_string(), "Q");
+    }
+
+    #[test]
+    fn queenside_block_mask() {
+        let castle = CastleRights::default();
+
+        assert_eq!(
+            castle.queenside_block_mask(Square::E1, Color::White),
+            Square::B1.mask().with(Square::C1).with(Square::D1)
+        );
+        assert_eq!(
+            castle.queenside_block_mask(Square::E8, Color::Black),
+            Square::B8.mask().with(Square::C8).with(Square::D8)
+        );
+    }
+
+    #[test]
+    fn queenside_block_mask_knight_on_b1() {
+        let position = FenParser::parse("4k3/8/8/8/8/8/8/RN2K2R w KQ - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+        let castle = CastleRights::default();
+
+        assert!(castle
+            .block_mask(Square::E1, Color::White, CastleDir::Long)
+            .intersects(position.occupied()));
+        assert!(!castle
+            .block_mask(Square::E1, Color::White, CastleDir::Short)
+            .intersects(position.occupied()));
+    }
+}
@@ -39,6 +39,15 @@ impl Color {
         }
     }
 
+    /// The rank on which the pawns of the color start,
+    /// and from which they may push two squares.
+    pub fn pawn_rank(self) -> Rank {
+        match self {
+            Self::White => Rank::_2,
+            Self::Black => Rank::_7,
+        }
+    }
+
     /// 'w' for white, 'b' for black.
     pub fn to_char(self) -> char {
         match self {
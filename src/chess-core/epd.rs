@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use crate::castle::CastleRights;
+use crate::color::Color;
+use crate::fen::{FenParseError, FenParser};
+use crate::position::Position;
+use crate::square::Square;
+
+/// EPD is the first four fields of a FEN, followed by a list of
+/// 'opcode operands;' operations, for example 'bm Nf3; id "WAC.001";'.
+/// The halfmoves and fullmoves are not included, so they default to 0 and 1.
+pub struct EpdParser<'a> {
+    fen: FenParser<'a>,
+    operations: &'a str,
+}
+
+impl<'a> EpdParser<'a> {
+    /// Parse an EPD string into an EpdParser struct.
+    /// This function will return an error if any
+    /// of the four position fields are missing.
+    pub fn parse(epd: &'a str) -> Result<Self, FenParseError> {
+        let mut fields = ["", "", "", "", "0", "1"];
+        let mut rest = epd.trim_start();
+
+        for field in fields[..4].iter_mut() {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            *field = &rest[..end];
+            rest = rest[end..].trim_start();
+        }
+
+        if fields[3].is_empty() {
+            return Err(FenParseError::MissingInfo);
+        }
+
+        Ok(Self {
+            fen: FenParser::from_fields(fields),
+            operations: rest,
+        })
+    }
+
+    /// Get the position from the epd, complete with the
+    /// en passant square. The halfmoves are always 0.
+    pub fn position(&self) -> Result<Position, FenParseError> {
+        self.fen.position()
+    }
+
+    /// Get the color of the player to move.
+    pub fn turn(&self) -> Result<Color, FenParseError> {
+        self.fen.turn()
+    }
+
+    /// Parse the castle rights, in the format KQkq.
+    pub fn castle(&self) -> Result<CastleRights, FenParseError> {
+        self.fen.castle()
+    }
+
+    /// Get the en passant square available in the position.
+    pub fn en_passant(&self) -> Result<Option<Square>, FenParseError> {
+        self.fen.en_passant()
+    }
+
+    /// The halfmoves, which EPD doesn't record, so always 0.
+    pub fn halfmoves(&self) -> u8 {
+        0
+    }
+
+    /// The fullmoves, which EPD doesn't record, so always 1.
+    pub fn fullmoves(&self) -> u16 {
+        1
+    }
+
+    /// The operations after the position, mapping each opcode to its operands.
+    /// Quotes around a string operand are removed, and semicolons inside
+    /// quotes don't end the operation.
+    pub fn operations(&self) -> HashMap<String, String> {
+        let mut result = HashMap::new();
+        let mut in_quotes = false;
+        let mut start = 0;
+
+        for (i, c) in self.operations.char_indices() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                ';' if !in_quotes => {
+                    insert_operation(&mut result, &self.operations[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+
+        // the last operation may be missing its semicolon.
+        insert_operation(&mut result, &self.operations[start..]);
+
+        result
+    }
+}
+
+/// Split an operation into its opcode and operands and add it to the map.
+fn insert_operation(operations: &mut HashMap<String, String>, operation: &str) {
+    let operation = operation.trim();
+
+    if operation.is_empty() {
+        return;
+    }
+
+    let (opcode, operands) = operation
+        .split_once(char::is_whitespace)
+        .unwrap_or((operation, ""));
+    let operands = operands.trim();
+    let operands = operands
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .unwrap_or(operands);
+
+    operations.insert(opcode.to_string(), operands.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() -> Result<(), FenParseError> {
+        let parser = EpdParser::parse(
+            "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";",
+        )?;
+
+        let operations = parser.operations();
+
+        assert_eq!(parser.turn()?, Color::White);
+        assert_eq!(parser.castle()?, CastleRights::none());
+        assert_eq!(parser.en_passant()?, None);
+        assert_eq!(parser.position()?.halfmoves(), 0);
+        assert_eq!(parser.fullmoves(), 1);
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations["bm"], "Qg6");
+        assert_eq!(operations["id"], "WAC.001");
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_default_position() -> Result<(), FenParseError> {
+        let parser = EpdParser::parse(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - c0 \"start; of game\"",
+        )?;
+
+        assert_eq!(parser.position()?, Position::default());
+        assert_eq!(parser.castle()?, CastleRights::default());
+        assert_eq!(parser.operations()["c0"], "start; of game");
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_missing_info() {
+        assert!(EpdParser::parse("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq").is_err());
+    }
+}
@@ -22,6 +22,11 @@ impl<'a> FenParser<'a> {
             .map_err(|_| FenParseError::MissingInfo)
     }
 
+    /// Create a parser from the six space-separated fields of a FEN.
+    pub(crate) fn from_fields(fields: [&'a str; 6]) -> Self {
+        Self(fields)
+    }
+
     /// Get the position from the fen, complete with
     /// the en passant square and the halfmoves number.
     pub fn position(&self) -> Result<Position, FenParseError> {
@@ -46,7 +51,7 @@ impl<'a> FenParser<'a> {
                         let rank = 7 - square.rank() as u8;
 
                         if let Some(square) = Square::try_new(file, rank) {
-                            masks[2 + piece.index()].set(square);
+                            masks[piece.mask_index()].set(square);
                             masks[Color::of_char(c) as usize].set(square);
                             index += 1;
                             continue;
@@ -129,8 +134,10 @@ impl<'a> FenParser<'a> {
                 // if true, this is the kingside rook file because
                 // it is to the right of the king.
                 if (file as i8 - dir as i8).is_positive() {
+                    rights = rights.with_kingside_rook_file(file);
                     rights.give(Color::of_char(c), CastleDir::Short);
                 } else {
+                    rights = rights.with_queenside_rook_file(file);
                     rights.give(Color::of_char(c), CastleDir::Long);
                 }
             } else {
@@ -142,6 +149,35 @@ impl<'a> FenParser<'a> {
         Ok(rights)
     }
 
+    /// Check that the position is one that could occur in a game: each color has
+    /// exactly one king, there are no pawns on the first or last rank, and the
+    /// side that just moved did not leave its king in check.
+    pub fn validate(&self) -> Result<(), FenParseError> {
+        let position = self.position()?;
+        let turn = self.turn()?;
+
+        for color in [Color::White, Color::Black] {
+            match (position.kings() & position.color_mask(color)).count() {
+                0 => return Err(FenParseError::MissingKings),
+                1 => {}
+                _ => return Err(FenParseError::TooManyKings),
+            }
+        }
+
+        if position.pawns().intersects(Bitmask::RANK1 | Bitmask::RANK8) {
+            return Err(FenParseError::PawnOnBackRank);
+        }
+
+        let king = (position.kings() & position.color_mask(!turn))
+            .first()
+            .unwrap();
+        if position.is_square_attacked(king, turn) {
+            return Err(FenParseError::OpponentInCheck);
+        }
+
+        Ok(())
+    }
+
     /// Get the en passant square available in the position.
     /// This should be '-' if en passant is not available.
     pub fn en_passant(&self) -> Result<Option<Square>, FenParseError> {
@@ -156,10 +192,11 @@ impl<'a> FenParser<'a> {
         }
     }
 
-    /// Get the halfmoves of the position.
+    /// Get the halfmoves of the position. The clock counts plies, so it can
+    /// legitimately reach 100 under the 50 move rule and 150 under the 75 move rule.
     pub fn halfmoves(&self) -> Result<u8, FenParseError> {
         if let Ok(halfmoves) = self.0[4].parse::<u8>() {
-            if halfmoves > 50 {
+            if halfmoves > 150 {
                 Err(FenParseError::BadHalfmoves)
             } else {
                 Ok(halfmoves)
@@ -179,7 +216,7 @@ impl<'a> FenParser<'a> {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum FenParseError {
     MissingInfo,
     BadCastle,
@@ -189,8 +226,37 @@ pub enum FenParseError {
     BadHalfmoves,
     BadFullmoves,
     MissingKings,
+    TooManyKings,
+    PawnOnBackRank,
+    OpponentInCheck,
+    IllegalCastleRights,
+}
+
+impl std::fmt::Display for FenParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::MissingInfo => "fen is missing one or more fields",
+            Self::BadCastle => "castling field malformed",
+            Self::BadPosition => "piece placement field malformed",
+            Self::BadTurn => "side to move must be 'w' or 'b'",
+            Self::BadEnPassant => "en passant field malformed",
+            Self::BadHalfmoves => "halfmove clock malformed or out of range",
+            Self::BadFullmoves => "fullmove number malformed",
+            Self::MissingKings => "each side must have a king",
+            Self::TooManyKings => "each side must have exactly one king",
+            Self::PawnOnBackRank => "pawns can't stand on the first or last rank",
+            Self::OpponentInCheck => "the side not to move is in check",
+            Self::IllegalCastleRights => {
+                "castling rights without a king and rook on their home squares"
+            }
+        };
+
+        write!(f, "{message}")
+    }
 }
 
+impl std::error::Error for FenParseError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,4 +281,65 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn halfmoves() -> Result<(), FenParseError> {
+        let parser = FenParser::parse("4k3/8/8/8/8/8/8/4K3 w - - 99 120")?;
+        assert_eq!(parser.halfmoves()?, 99);
+
+        let parser = FenParser::parse("4k3/8/8/8/8/8/8/4K3 w - - 150 120")?;
+        assert_eq!(parser.halfmoves()?, 150);
+
+        let parser = FenParser::parse("4k3/8/8/8/8/8/8/4K3 w - - 151 120")?;
+        assert!(parser.halfmoves().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate() {
+        let validate = |fen| FenParser::parse(fen).unwrap().validate();
+
+        assert!(validate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").is_ok());
+        assert!(matches!(
+            validate("8/8/8/8/8/8/8/8 w - - 0 1"),
+            Err(FenParseError::MissingKings)
+        ));
+        assert!(matches!(
+            validate("4k3/8/8/8/8/8/8/3KK3 w - - 0 1"),
+            Err(FenParseError::TooManyKings)
+        ));
+        assert!(matches!(
+            validate("3Pk3/8/8/8/8/8/8/4K3 w - - 0 1"),
+            Err(FenParseError::PawnOnBackRank)
+        ));
+        // white is to move, but black's king is already in check from the rook.
+        assert!(matches!(
+            validate("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1"),
+            Err(FenParseError::OpponentInCheck)
+        ));
+    }
+
+    #[test]
+    fn error_display() {
+        for error in [
+            FenParseError::MissingInfo,
+            FenParseError::BadCastle,
+            FenParseError::BadPosition,
+            FenParseError::BadTurn,
+            FenParseError::BadEnPassant,
+            FenParseError::BadHalfmoves,
+            FenParseError::BadFullmoves,
+            FenParseError::MissingKings,
+            FenParseError::TooManyKings,
+            FenParseError::PawnOnBackRank,
+            FenParseError::OpponentInCheck,
+            FenParseError::IllegalCastleRights,
+        ] {
+            assert!(!error.to_string().is_empty());
+        }
+
+        let error: Box<dyn std::error::Error> = Box::new(FenParseError::BadCastle);
+        assert_eq!(error.to_string(), "castling field malformed");
+    }
 }
@@ -2,11 +2,12 @@ use crate::castle::CastleRights;
 use crate::color::Color;
 use crate::piece::Piece;
 use crate::position::Position;
+use crate::record::{MoveRecord, RecordedMove};
 use crate::square::Square;
 use crate::state::BoardState;
 
-/// A Representation of a chess game.
-/// NOTE: this does not record moves, only positions.
+/// A Representation of a chess game, recording
+/// the position and the move played at every halfmove.
 #[derive(Clone, Debug, Hash)]
 pub struct ChessGame {
     /// The initial (starting position) of the game.
@@ -17,9 +18,30 @@ pub struct ChessGame {
     last: BoardState,
     /// The position at every halfmove.
     history: Vec<Position>,
+    /// The move that led to every position in 'history'
+    /// after the first, in Standard Algebraic Notation.
+    record: MoveRecord,
+    /// States and moves taken off the end of the history
+    /// by 'undo', the most recently undone state last.
+    redo: Vec<(BoardState, RecordedMove)>,
+    /// The index in 'history' of the position being viewed, which
+    /// can be moved around without changing the game itself.
+    cursor: usize,
 }
 
 impl ChessGame {
+    /// Create a new game starting from the given state.
+    pub fn new(first: BoardState) -> Self {
+        Self {
+            first,
+            last: first,
+            history: vec![first.position()],
+            record: MoveRecord::starting_at(first.fullmoves(), first.turn()),
+            redo: Vec::new(),
+            cursor: 0,
+        }
+    }
+
     /// Get the starting position.
     pub fn first(&self) -> &BoardState {
         &self.first
@@ -30,6 +52,16 @@ impl ChessGame {
         &self.last
     }
 
+    /// The moves played in the game.
+    pub fn record(&self) -> &MoveRecord {
+        &self.record
+    }
+
+    /// The moves played in the game, to annotate them.
+    pub(crate) fn record_mut(&mut self) -> &mut MoveRecord {
+        &mut self.record
+    }
+
     /// The number of moves stored in the game's history.
     pub fn len(&self) -> usize {
         self.history.len()
@@ -58,7 +90,10 @@ impl ChessGame {
             Some(Self {
                 first: self.first,
                 last: self.state_at_index(index)?,
-                history: self.history[..index].to_vec(),
+                history: self.history[..=index].to_vec(),
+                record: self.record_until(index),
+                redo: Vec::new(),
+                cursor: index,
             })
         }
     }
@@ -67,25 +102,39 @@ impl ChessGame {
     pub fn clear_after(&mut self, index: usize) {
         if index < self.history.len() {
             self.last = self.state_at_index(index).unwrap();
-            self.history = self.history[index..].to_vec();
+            self.history.truncate(index + 1);
+            self.record = self.record_until(index);
+            self.redo.clear();
+            self.cursor = self.history.len() - 1;
+        }
+    }
+
+    /// The moves that led to the position at the index in history.
+    fn record_until(&self, index: usize) -> MoveRecord {
+        match index {
+            0 => MoveRecord::starting_at(self.first.fullmoves(), self.first.turn()),
+            _ => self.record.fork_at(index - 1),
         }
     }
 
     /// Get the number of fullmoves at the index in history.
     pub fn fullmoves_at_index(&self, index: usize) -> u16 {
-        // if black went first, offset by 1.
+        // fullmoves increment when black moves, so if black went
+        // first the increment happens one ply earlier.
         self.first.fullmoves()
             + if self.first.turn() == Color::Black {
-                (index as u16 + 3) / 2
+                (index as u16).div_ceil(2)
             } else {
-                (index as u16 + 2) / 2
+                index as u16 / 2
             }
     }
 
     /// Get the castle rights at the index.
     pub fn castle_rights_at_index(&self, index: usize) -> CastleRights {
         let fullmoves = self.fullmoves_at_index(index);
-        self.last.castle().index(index as u16)
+        self.last
+            .castle()
+            .index(fullmoves, self.turn_at_index(index))
     }
 
     /// Get the color of the turn at the index.
@@ -105,8 +154,78 @@ impl ChessGame {
 
     /// Play a move, assuming it has been validated by a MoveGenerator.
     pub fn play(&mut self, from: Square, dest: Square, promotion: Option<Piece>) {
+        let notation = self.last.notation_annotated(from, dest, promotion);
+        self.record.write(from, dest, notation);
         self.last = self.last.play_unchecked(from, dest, promotion);
         self.history.push(self.last.position());
+        self.redo.clear();
+        self.cursor = self.history.len() - 1;
+    }
+
+    /// Take back the most recent move, returning the state that is now the
+    /// last. The move can be replayed with 'redo' until another move is played.
+    /// Returns None if there are no moves to take back.
+    pub fn undo(&mut self) -> Option<BoardState> {
+        if self.history.len() < 2 {
+            return None;
+        }
+
+        // the full state is kept, since the castle rights of
+        // earlier states don't know about castle lost later.
+        self.redo.push((self.last, self.record.pop()?));
+        self.history.pop();
+        self.cursor = self.history.len() - 1;
+        self.last = self.state_at_index(self.cursor)?;
+        Some(self.last)
+    }
+
+    /// Replay the most recently undone move, returning the state that is now the last.
+    /// Returns None if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<BoardState> {
+        let (state, mv) = self.redo.pop()?;
+        self.record.push(mv);
+        self.last = state;
+        self.history.push(state.position());
+        self.cursor = self.history.len() - 1;
+        Some(state)
+    }
+
+    /// The index in history of the position being viewed. Playing, undoing,
+    /// or redoing a move moves the cursor to the last position.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The state at the cursor.
+    pub fn current_state(&self) -> BoardState {
+        if self.cursor == self.history.len() - 1 {
+            self.last
+        } else {
+            self.state_at_index(self.cursor).unwrap()
+        }
+    }
+
+    /// Move the cursor to the index, returning the state there.
+    /// Returns None and leaves the cursor alone if the index is out of range.
+    pub fn goto(&mut self, index: usize) -> Option<BoardState> {
+        if index < self.history.len() {
+            self.cursor = index;
+            Some(self.current_state())
+        } else {
+            None
+        }
+    }
+
+    /// Move the cursor forward by a ply, returning the state there.
+    /// Returns None if the cursor is already on the last position.
+    pub fn next_ply(&mut self) -> Option<BoardState> {
+        self.goto(self.cursor + 1)
+    }
+
+    /// Move the cursor back by a ply, returning the state there.
+    /// Returns None if the cursor is already on the first position.
+    pub fn prev_ply(&mut self) -> Option<BoardState> {
+        self.goto(self.cursor.checked_sub(1)?)
     }
 
     /// Get the previous position.
@@ -118,45 +237,711 @@ impl ChessGame {
         }
     }
 
-    /// This function will return true if the same
-    /// position occurs 3 times, only checking for
-    /// the most recent position.
-    pub fn is_draw_by_repetition(&self) -> bool {
-        let mut one = false;
+    /// The number of times the most recent position has occurred
+    /// in the game's history, including the most recent position itself.
+    /// Positions only count as the same if they have the same castle rights
+    /// and the same en passant capture available, as FIDE requires.
+    pub fn repetition_count(&self) -> usize {
+        let last_index = self.history.len() - 1;
+        let last = self.last.position();
+        let last_castle = self.castle_flags_at_index(last_index);
+        let last_en_passant = self.en_passant_at_index(last_index);
+        let mut count = 0;
+
+        // a position can only repeat if the same player is up to
+        // move, so step back through the history two plies at a time.
+        for index in (0..=last_index).rev().step_by(2) {
+            let pos = &self.history[index];
 
-        for pos in self.history.iter().rev().skip(1) {
             // pawn moves can't be reversed.
-            if pos.pawns() != self.last.position().pawns() {
-                return false;
+            if pos.pawns() != last.pawns() {
+                break;
             }
 
             // captures can't be reversed.
-            if pos.count() != self.last.position().count() {
-                return false;
+            if pos.count() != last.count() {
+                break;
+            }
+
+            // lost castle rights can't be regained.
+            if self.castle_flags_at_index(index) != last_castle {
+                break;
             }
 
             // detect equal positions.
-            if pos.masks() == self.last.position().masks() {
-                if one {
-                    return true;
-                } else {
-                    one = true;
-                }
+            if pos.masks() == last.masks() && self.en_passant_at_index(index) == last_en_passant {
+                count += 1;
             }
         }
 
-        false
+        count
+    }
+
+    /// The castle rights at the index, as (white kingside,
+    /// white queenside, black kingside, black queenside).
+    fn castle_flags_at_index(&self, index: usize) -> (bool, bool, bool, bool) {
+        self.castle_rights_at_index(index)
+            .as_flags(self.fullmoves_at_index(index))
+    }
+
+    /// The en passant square at the index, but only if a pawn of the player up to move
+    /// is in position to capture on it. The square is set after every double push,
+    /// even if no capture is possible, which doesn't make the position any different.
+    fn en_passant_at_index(&self, index: usize) -> Option<Square> {
+        let pos = &self.history[index];
+        let turn = self.turn_at_index(index);
+
+        pos.en_passant().filter(|en_passant_sq| {
+            // look from the en passant square as an enemy pawn to find the capturers.
+            Piece::Pawn
+                .relevant_squares(*en_passant_sq, !turn)
+                .intersects(pos.pawns() & pos.color_mask(turn))
+        })
+    }
+
+    /// This function will return true if the same
+    /// position occurs 3 times, only checking for
+    /// the most recent position.
+    pub fn is_draw_by_repetition(&self) -> bool {
+        self.repetition_count() >= 3
+    }
+
+    /// The result of the game, or None if the game is still ongoing. The fifty-move
+    /// rule and threefold repetition are treated as draws as soon as they can be claimed.
+    pub fn result(&self) -> Option<GameResult> {
+        if self.last.is_checkmate() {
+            return Some(match self.last.turn() {
+                Color::White => GameResult::BlackWins,
+                Color::Black => GameResult::WhiteWins,
+            });
+        }
+
+        let reason = if self.last.is_stalemate() {
+            DrawReason::Stalemate
+        } else if self.last.position().is_insufficient_material() {
+            DrawReason::InsufficientMaterial
+        } else if self.is_draw_by_fifty_move_rule() {
+            DrawReason::FiftyMove
+        } else if self.is_draw_by_repetition() {
+            DrawReason::Repetition
+        } else {
+            return None;
+        };
+
+        Some(GameResult::Draw(reason))
+    }
+
+    /// Export the game as PGN. The Seven Tag Roster is always included, with '?'
+    /// for any tag not given in 'tags', and the 'Result' tag derived from 'result'
+    /// unless it is given. Any other tags follow the roster in the order given.
+    /// Games that don't start from the standard position get 'SetUp' and 'FEN' tags.
+    pub fn to_pgn(&self, tags: &[(&str, &str)]) -> String {
+        let result = match self.result() {
+            Some(GameResult::WhiteWins) => "1-0",
+            Some(GameResult::BlackWins) => "0-1",
+            Some(GameResult::Draw(_)) => "1/2-1/2",
+            None => "*",
+        };
+
+        let given = |name: &str| {
+            tags.iter()
+                .find(|(tag, _)| *tag == name)
+                .map(|(_, value)| *value)
+        };
+        let result = given("Result").unwrap_or(result);
+        let fen = self.first.to_fen();
+        let mut roster = vec![
+            ("Event", given("Event").unwrap_or("?")),
+            ("Site", given("Site").unwrap_or("?")),
+            ("Date", given("Date").unwrap_or("????.??.??")),
+            ("Round", given("Round").unwrap_or("?")),
+            ("White", given("White").unwrap_or("?")),
+            ("Black", given("Black").unwrap_or("?")),
+            ("Result", result),
+        ];
+
+        if self.first != BoardState::default() {
+            roster.push(("SetUp", "1"));
+            roster.push(("FEN", &fen));
+        }
+
+        let mut pgn = String::new();
+
+        for (name, value) in roster.iter().chain(
+            tags.iter()
+                .filter(|(name, _)| !roster.iter().any(|(tag, _)| tag == name)),
+        ) {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            pgn.push_str(&format!("[{name} \"{value}\"]\n"));
+        }
+
+        // the export format keeps the movetext lines within 80 columns.
+        let mut line = String::new();
+        pgn.push('\n');
+
+        let movetext = self.record.to_movetext();
+
+        for token in movetext.split_whitespace().chain([result]) {
+            if !line.is_empty() && line.len() + 1 + token.len() > 80 {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+
+            line.push_str(token);
+        }
+
+        pgn.push_str(&line);
+        pgn.push('\n');
+        pgn
+    }
+
+    /// Whether the side to move can claim a draw by the 50-move rule, which is
+    /// when 50 moves by each player have passed without a pawn move or capture.
+    /// A checkmate delivered on the 50th move still wins, so the side to
+    /// move must have a legal move.
+    pub fn is_draw_by_fifty_move_rule(&self) -> bool {
+        self.last.halfmoves() >= 100 && self.last.generator().has_any_moves()
+    }
+
+    /// Whether the game is drawn automatically by the 75-move rule, which
+    /// unlike the 50-move rule needs no claim. Checkmate still takes precedence.
+    pub fn is_draw_by_seventy_five_move_rule(&self) -> bool {
+        self.last.halfmoves() >= 150 && !self.last.is_checkmate()
     }
 }
 
+/// How a game ended, as returned by 'ChessGame::result'.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GameResult {
+    /// White checkmated black.
+    WhiteWins,
+    /// Black checkmated white.
+    BlackWins,
+    /// Neither player won.
+    Draw(DrawReason),
+}
+
+/// Why a game ended in a draw.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DrawReason {
+    /// The player up to move is not in check, but has no legal moves.
+    Stalemate,
+    /// 50 moves by each player without a pawn move or capture.
+    FiftyMove,
+    /// The same position occurred three times.
+    Repetition,
+    /// Neither player has enough material left to checkmate.
+    InsufficientMaterial,
+}
+
 impl Default for ChessGame {
     fn default() -> Self {
-        let default_pos = BoardState::default();
+        Self::new(BoardState::default())
+    }
+}
 
-        Self {
-            first: default_pos,
-            last: default_pos,
-            history: vec![Position::default()],
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pgn::PgnParser;
+    use crate::record::MoveString;
+
+    /// Shuffle the knights out and back twice, so the
+    /// start position occurs three times in the history.
+    fn knight_shuffle() -> ChessGame {
+        let mut game = ChessGame::default();
+
+        for _ in 0..2 {
+            game.play(Square::G1, Square::F3, None);
+            game.play(Square::G8, Square::F6, None);
+            game.play(Square::F3, Square::G1, None);
+            game.play(Square::F6, Square::G8, None);
         }
+
+        game
+    }
+
+    #[test]
+    fn repetition_count() {
+        let game = knight_shuffle();
+
+        assert_eq!(game.repetition_count(), 3);
+        assert!(game.is_draw_by_repetition());
+    }
+
+    #[test]
+    fn repetition_count_after_fork() {
+        let game = knight_shuffle();
+
+        // the start position has occurred twice by index 4.
+        let fork = game.fork(4).unwrap();
+        assert_eq!(fork.repetition_count(), 2);
+        assert!(!fork.is_draw_by_repetition());
+
+        // the position at index 3 only repeats after the fork point.
+        let fork = game.fork(3).unwrap();
+        assert_eq!(fork.repetition_count(), 1);
+    }
+
+    #[test]
+    fn fifty_move_rule() {
+        let state = BoardState::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+        let mut game = ChessGame::new(state);
+        assert!(!game.is_draw_by_fifty_move_rule());
+
+        game.play(Square::A1, Square::A2, None);
+        assert_eq!(game.last().halfmoves(), 100);
+        assert!(game.is_draw_by_fifty_move_rule());
+        assert!(!game.is_draw_by_seventy_five_move_rule());
+    }
+
+    #[test]
+    fn fifty_move_rule_checkmate() {
+        // the 100th halfmove is checkmate, so there is no draw to claim.
+        let state = BoardState::from_fen("6k1/8/6K1/8/8/8/8/R7 w - - 99 80").unwrap();
+        let mut game = ChessGame::new(state);
+
+        game.play(Square::A1, Square::A8, None);
+        assert!(game.last().is_checkmate());
+        assert!(!game.is_draw_by_fifty_move_rule());
+    }
+
+    #[test]
+    fn seventy_five_move_rule() {
+        let state = BoardState::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 149 100").unwrap();
+        let mut game = ChessGame::new(state);
+        assert!(!game.is_draw_by_seventy_five_move_rule());
+
+        game.play(Square::A1, Square::A2, None);
+        assert!(game.is_draw_by_seventy_five_move_rule());
+    }
+
+    #[test]
+    fn fullmoves_at_index() {
+        let game = knight_shuffle();
+
+        assert_eq!(game.fullmoves_at_index(0), 1);
+        assert_eq!(game.fullmoves_at_index(1), 1);
+        assert_eq!(game.fullmoves_at_index(2), 2);
+
+        for index in 0..game.len() {
+            assert_eq!(
+                game.state_at_index(index).unwrap().fullmoves(),
+                1 + index as u16 / 2
+            );
+        }
+
+        // black goes first.
+        let state = BoardState::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 7").unwrap();
+        let mut game = ChessGame::new(state);
+        game.play(Square::E8, Square::E7, None);
+        game.play(Square::E1, Square::E2, None);
+
+        assert_eq!(game.fullmoves_at_index(0), 7);
+        assert_eq!(game.fullmoves_at_index(1), 8);
+        assert_eq!(game.fullmoves_at_index(2), 8);
+        assert_eq!(game.last().fullmoves(), 8);
+    }
+
+    #[test]
+    fn castle_rights_at_index() {
+        let mut game = ChessGame::default();
+
+        // 1.e4 e5 2.Nf3 Nc6 3.Bc4 Bc5 4.d3 d6 5.O-O
+        for (from, dest) in [
+            (Square::E2, Square::E4),
+            (Square::E7, Square::E5),
+            (Square::G1, Square::F3),
+            (Square::B8, Square::C6),
+            (Square::F1, Square::C4),
+            (Square::F8, Square::C5),
+            (Square::D2, Square::D3),
+            (Square::D7, Square::D6),
+            (Square::E1, Square::G1),
+        ] {
+            game.play(from, dest, None);
+        }
+
+        // white castles from the position at index 8, on move 5.
+        assert!(game
+            .castle_rights_at_index(8)
+            .has_kingside_castle(Color::White, 5));
+        assert!(game
+            .state_at_index(8)
+            .unwrap()
+            .castle()
+            .has_kingside_castle(Color::White, 5));
+        assert!(!game
+            .castle_rights_at_index(9)
+            .has_kingside_castle(Color::White, 5));
+        assert!(!game
+            .castle_rights_at_index(9)
+            .has_queenside_castle(Color::White, 5));
+
+        // black has not castled, so still has castle throughout.
+        for index in 0..game.len() {
+            assert!(game
+                .castle_rights_at_index(index)
+                .has_kingside_castle(Color::Black, 5));
+        }
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut game = ChessGame::default();
+        game.play(Square::E2, Square::E4, None);
+        game.play(Square::E7, Square::E5, None);
+        let after_e4 = game.state_at_index(1).unwrap();
+        let after_e5 = *game.last();
+
+        assert_eq!(game.undo(), Some(after_e4));
+        assert_eq!(game.undo(), Some(BoardState::default()));
+        assert_eq!(game.undo(), None);
+        assert_eq!(game.len(), 1);
+
+        assert_eq!(game.redo(), Some(after_e4));
+        assert_eq!(game.len(), 2);
+        assert_eq!(game.last(), &after_e4);
+
+        // playing a move forgets the undone moves.
+        game.play(Square::C7, Square::C5, None);
+        assert_eq!(game.redo(), None);
+        assert_ne!(game.last(), &after_e5);
+    }
+
+    #[test]
+    fn undo_redo_castle() {
+        let state = BoardState::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let mut game = ChessGame::new(state);
+        game.play(Square::E1, Square::G1, None);
+        let castled = *game.last();
+
+        assert_eq!(game.undo(), Some(state));
+        assert!(game.last().castle().has_kingside_castle(Color::White, 1));
+
+        // the redone state still knows castle was lost.
+        assert_eq!(game.redo(), Some(castled));
+        assert!(!game.last().castle().has_kingside_castle(Color::White, 1));
+    }
+
+    #[test]
+    fn castle_rights_high_fullmoves() {
+        // the ply is twice the fullmove number, which must not wrap or overflow.
+        for fen in [
+            "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 20000",
+            "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 40000",
+        ] {
+            let state = BoardState::from_fen(fen).unwrap();
+            let mut game = ChessGame::new(state);
+            game.play(Square::E1, Square::F1, None);
+
+            assert_eq!(game.last().castle().to_fen_string(), "kq");
+            assert_eq!(game.state_at_index(0), Some(state));
+            assert_eq!(game.state_at_index(1), Some(*game.last()));
+        }
+    }
+
+    #[test]
+    fn castle_rights_rook_captured() {
+        // white takes the black rook on its home square, on white's move.
+        let state = BoardState::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 5").unwrap();
+        let mut game = ChessGame::new(state);
+        game.play(Square::H1, Square::H8, None);
+
+        assert_eq!(game.last().castle().to_fen_string(), "Qq");
+        assert_eq!(game.state_at_index(0), Some(state));
+        assert_eq!(game.state_at_index(1), Some(*game.last()));
+
+        // black gets its kingside castle back once the capture is undone.
+        assert_eq!(game.undo(), Some(state));
+        assert_eq!(game.last().castle().to_fen_string(), "KQkq");
+
+        // black takes the white rook on its home square, on black's move.
+        let state = BoardState::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 5").unwrap();
+        let mut game = ChessGame::new(state);
+        game.play(Square::H8, Square::H1, None);
+
+        assert_eq!(game.last().castle().to_fen_string(), "Qq");
+        assert_eq!(game.state_at_index(0), Some(state));
+        assert_eq!(game.state_at_index(1), Some(*game.last()));
+
+        assert_eq!(game.undo(), Some(state));
+        assert_eq!(game.last().castle().to_fen_string(), "KQkq");
+    }
+
+    #[test]
+    fn cursor() {
+        let mut game = knight_shuffle();
+        let last = game.len() - 1;
+        assert_eq!(game.cursor(), last);
+        assert_eq!(game.current_state(), *game.last());
+
+        assert_eq!(game.next_ply(), None);
+        assert_eq!(game.prev_ply(), game.state_at_index(last - 1));
+        assert_eq!(game.prev_ply(), game.state_at_index(last - 2));
+        assert_eq!(game.cursor(), last - 2);
+        assert_eq!(game.next_ply(), game.state_at_index(last - 1));
+
+        assert_eq!(game.goto(0), Some(BoardState::default()));
+        assert_eq!(game.prev_ply(), None);
+        assert_eq!(game.goto(last + 1), None);
+        assert_eq!(game.cursor(), 0);
+
+        // navigating doesn't change the game.
+        assert_eq!(game.len(), last + 1);
+        for index in 0..game.len() {
+            game.goto(index);
+            assert_eq!(Some(game.current_state()), game.state_at_index(index));
+        }
+    }
+
+    #[test]
+    fn record() {
+        let mut game = ChessGame::default();
+        game.play(Square::E2, Square::E4, None);
+        game.play(Square::E7, Square::E5, None);
+
+        let record = game.record();
+        assert_eq!(record.index(0).unwrap().notation().as_str(), "e4");
+        assert_eq!(record.index(1).unwrap().notation().as_str(), "e5");
+        assert_eq!(
+            record.last(),
+            Some(&RecordedMove::new(
+                Square::E7,
+                Square::E5,
+                MoveString::from("e5").unwrap()
+            ))
+        );
+
+        // taking back a move takes it out of the record too.
+        game.undo();
+        assert_eq!(game.record().last().unwrap().notation().as_str(), "e4");
+        game.redo();
+        assert_eq!(game.record().last().unwrap().notation().as_str(), "e5");
+
+        // the fork only keeps the moves leading to its last position.
+        assert!(game.fork(0).unwrap().record().last().is_none());
+        assert_eq!(
+            game.fork(1)
+                .unwrap()
+                .record()
+                .last()
+                .unwrap()
+                .notation()
+                .as_str(),
+            "e4"
+        );
+    }
+
+    #[test]
+    fn record_annotated() {
+        let mut game = ChessGame::default();
+
+        // fool's mate.
+        for (from, dest) in [
+            (Square::F2, Square::F3),
+            (Square::E7, Square::E5),
+            (Square::G2, Square::G4),
+            (Square::D8, Square::H4),
+        ] {
+            game.play(from, dest, None);
+        }
+
+        assert_eq!(game.record().last().unwrap().notation().as_str(), "Qh4#");
+    }
+
+    #[test]
+    fn repetition_count_castle_rights() {
+        let mut game =
+            ChessGame::new(BoardState::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap());
+
+        // the kings step out and back, losing castle rights, so the
+        // start position looks the same but is not repeated.
+        for _ in 0..2 {
+            game.play(Square::E1, Square::E2, None);
+            game.play(Square::E8, Square::E7, None);
+            game.play(Square::E2, Square::E1, None);
+            game.play(Square::E7, Square::E8, None);
+        }
+
+        assert_eq!(game.repetition_count(), 2);
+        assert!(!game.is_draw_by_repetition());
+    }
+
+    #[test]
+    fn repetition_count_en_passant() {
+        let mut game =
+            ChessGame::new(BoardState::from_fen("4k3/3p4/8/4P3/8/8/8/4K3 b - - 0 1").unwrap());
+
+        // after d5, exd6 is possible, which it is not when the position comes back.
+        game.play(Square::D7, Square::D5, None);
+        for _ in 0..2 {
+            game.play(Square::E1, Square::E2, None);
+            game.play(Square::E8, Square::E7, None);
+            game.play(Square::E2, Square::E1, None);
+            game.play(Square::E7, Square::E8, None);
+        }
+
+        assert_eq!(game.repetition_count(), 2);
+
+        // a double push that nothing can capture doesn't make the position different.
+        let mut game =
+            ChessGame::new(BoardState::from_fen("4k3/3p4/8/8/8/8/8/4K3 b - - 0 1").unwrap());
+
+        game.play(Square::D7, Square::D5, None);
+        for _ in 0..2 {
+            game.play(Square::E1, Square::E2, None);
+            game.play(Square::E8, Square::E7, None);
+            game.play(Square::E2, Square::E1, None);
+            game.play(Square::E7, Square::E8, None);
+        }
+
+        assert_eq!(game.repetition_count(), 3);
+    }
+
+    #[test]
+    fn result_checkmate() {
+        let mut game = ChessGame::default();
+        assert_eq!(game.result(), None);
+
+        // fool's mate.
+        for (from, dest) in [
+            (Square::F2, Square::F3),
+            (Square::E7, Square::E5),
+            (Square::G2, Square::G4),
+            (Square::D8, Square::H4),
+        ] {
+            game.play(from, dest, None);
+        }
+
+        assert_eq!(game.result(), Some(GameResult::BlackWins));
+    }
+
+    #[test]
+    fn result_draw() {
+        let mut game =
+            ChessGame::new(BoardState::from_fen("7k/8/5Q2/6K1/8/8/8/8 w - - 0 1").unwrap());
+        game.play(Square::F6, Square::F7, None);
+
+        assert_eq!(game.result(), Some(GameResult::Draw(DrawReason::Stalemate)));
+        assert_eq!(
+            knight_shuffle().result(),
+            Some(GameResult::Draw(DrawReason::Repetition))
+        );
+
+        let game = ChessGame::new(BoardState::from_fen("4k3/8/8/8/8/8/8/2N1K3 w - - 0 1").unwrap());
+        assert_eq!(
+            game.result(),
+            Some(GameResult::Draw(DrawReason::InsufficientMaterial))
+        );
+    }
+
+    #[test]
+    fn fork_len_and_last_agree() {
+        let game = knight_shuffle();
+
+        for index in 0..game.len() {
+            let fork = game.fork(index).unwrap();
+
+            assert_eq!(fork.len(), index + 1);
+            assert_eq!(fork.last(), &game.state_at_index(index).unwrap());
+            assert_eq!(fork.state_at_index(index), Some(*fork.last()));
+        }
+
+        assert!(game.fork(game.len()).is_none());
+    }
+
+    #[test]
+    fn clear_after() {
+        let mut game = knight_shuffle();
+        let state = game.state_at_index(3).unwrap();
+
+        game.clear_after(3);
+        assert_eq!(game.len(), 4);
+        assert_eq!(game.last(), &state);
+        assert_eq!(game.state_at_index(3), Some(state));
+        assert_eq!(game.record().last().unwrap().notation().as_str(), "Ng1");
+
+        // clearing after the last index changes nothing.
+        game.clear_after(3);
+        assert_eq!(game.len(), 4);
+    }
+
+    #[test]
+    fn to_pgn() {
+        let mut game = ChessGame::default();
+
+        // fool's mate.
+        for (from, dest) in [
+            (Square::F2, Square::F3),
+            (Square::E7, Square::E5),
+            (Square::G2, Square::G4),
+            (Square::D8, Square::H4),
+        ] {
+            game.play(from, dest, None);
+        }
+
+        let pgn = game.to_pgn(&[
+            ("White", "Fool"),
+            ("Annotator", "Me"),
+            ("Event", "A \"Game\""),
+        ]);
+
+        assert_eq!(
+            pgn,
+            r#"[Event "A \"Game\""]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "Fool"]
+[Black "?"]
+[Result "0-1"]
+[Annotator "Me"]
+
+1. f3 e5 2. g4 Qh4# 0-1
+"#
+        );
+    }
+
+    #[test]
+    fn to_pgn_round_trip() {
+        let pgn = "1.e4 e5 2.f4 exf4 3.Bc4 Qh4+ 4.Kf1 b5 5.Bxb5 Nf6 6.Nf3 Qh6 7.d3 Nh5 8.Nh4 Qg5 \
+                   9.Nf5 c6 10.g4 Nf6 11.Rg1 cxb5 12.h4 Qg6 13.h5 Qg5 14.Qf3 Ng8 15.Bxf4 Qf6 \
+                   16.Nc3 Bc5 17.Nd5 Qxb2 18.Bd6 Bxg1 19.e5 Qxa1+ 20.Ke2 Na6 21.Nxg7+ Kd8 \
+                   22.Qf6+ Nxf6 23.Be7# 1-0";
+        let game = PgnParser::new(pgn).unwrap().game().unwrap();
+        let exported = game.to_pgn(&[]);
+
+        assert!(exported.lines().all(|line| line.len() <= 80));
+        assert!(exported.trim_end().ends_with("23. Be7# 1-0"));
+
+        let parsed = PgnParser::new(&exported).unwrap();
+        assert_eq!(parsed.tag("Result"), Some("1-0"));
+
+        let reparsed = parsed.game().unwrap();
+        assert_eq!(reparsed.len(), game.len());
+        for index in 0..game.len() - 1 {
+            assert_eq!(reparsed.record().index(index), game.record().index(index));
+        }
+    }
+
+    #[test]
+    fn to_pgn_black_first() {
+        let state = BoardState::from_fen("6k1/p4ppp/8/8/8/8/5PPP/3R2K1 b - - 0 30").unwrap();
+        let mut game = ChessGame::new(state);
+        game.play(Square::A7, Square::A6, None);
+        game.play(Square::D1, Square::D8, None);
+
+        let pgn = game.to_pgn(&[]);
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"6k1/p4ppp/8/8/8/8/5PPP/3R2K1 b - - 0 30\"]\n"));
+        assert!(pgn.ends_with("\n30... a6 31. Rd8# 1-0\n"));
+
+        let parsed = PgnParser::new(&pgn).unwrap().game().unwrap();
+        assert_eq!(parsed.last(), game.last());
     }
 }
@@ -10,6 +10,10 @@ use crate::piece::Piece;
 use crate::position::Position;
 use crate::square::Square;
 use crate::state::BoardState;
+use arrayvec::ArrayVec;
+
+/// shorthand for a move in the format (from, dest, promotion).
+pub type Move = (Square, Square, Option<Piece>);
 
 /// A struct that contains information required to
 /// efficiently generate possible moves in a position
@@ -69,26 +73,86 @@ impl MoveGenerator {
 
     /// Generate the valid moves for a piece at the square.
     /// This function will return Bitmask::EMPTY if it is not
-    /// the pieces' turn to move.
+    /// the pieces' turn to move, or if the player up to move has no king.
     pub fn generate(&self, square: Square) -> Bitmask {
-        if let Some((color, piece)) = self.position.piece_at(square) {
+        if let (Some((color, piece)), Some(king)) = (self.position.piece_at(square), self.king()) {
             if color == self.turn {
-                return self.generate_internal(piece, square, self.king());
+                return self.generate_internal(piece, square, king);
             }
         }
 
         Bitmask::EMPTY
     }
 
+    /// Generate the valid moves for a piece at the square that capture an enemy
+    /// piece, including en passant. Useful for quiescence search.
+    pub fn generate_captures(&self, square: Square) -> Bitmask {
+        self.generate(square) & self.capture_targets(square)
+    }
+
+    /// Generate the valid moves for a piece at the square that don't capture,
+    /// which is everything 'generate_captures' leaves out, castling included.
+    pub fn generate_quiet(&self, square: Square) -> Bitmask {
+        self.generate(square)
+            .intersection(self.capture_targets(square))
+    }
+
+    /// Sort the moves most valuable victim first, then least valuable attacker,
+    /// so captures that win material are searched before the ones that risk it.
+    /// Moves that don't capture go last, in the order they were in.
+    pub fn order_captures(&self, moves: &mut [(Square, Square)]) {
+        moves.sort_by_key(|&(from, dest)| std::cmp::Reverse(self.mvv_lva(from, dest)));
+    }
+
+    /// The MVV-LVA score of a move, or None if it isn't a capture.
+    fn mvv_lva(&self, from: Square, dest: Square) -> Option<i32> {
+        if !self.capture_targets(from).has(dest) {
+            return None;
+        }
+
+        // the en passant square is empty, but the victim is always a pawn.
+        let (_, victim) = self
+            .position
+            .piece_at(dest)
+            .unwrap_or((!self.turn, Piece::Pawn));
+        let (_, attacker) = self.position.piece_at(from)?;
+
+        Some(victim.value() * 10 - attacker.value())
+    }
+
     /// Whether the king is in check.
     pub fn is_check(&self) -> bool {
         !self.checking.is_empty()
     }
 
+    /// The mask of squares occupied by friendly pieces that
+    /// are pinned to the king by an enemy slider.
+    pub fn pinned(&self) -> Bitmask {
+        self.pinned
+    }
+
+    /// The mask of squares occupied by enemy pieces giving check.
+    pub fn checkers(&self) -> Bitmask {
+        self.checking
+    }
+
+    /// The number of enemy pieces giving check, 2 being a double check.
+    pub fn checker_count(&self) -> u8 {
+        self.checking.count()
+    }
+
+    /// The mask of squares attacked by the opponent. Sliders
+    /// see through the king, so the squares behind it are included.
+    pub fn attacked_squares(&self) -> Bitmask {
+        self.defense
+    }
+
     /// Returns true if ANY piece in the position has a valid move.
     pub fn has_any_moves(&self) -> bool {
         let friendly = self.position.color_mask(self.turn);
-        let king = self.king();
+        let Some(king) = self.king() else {
+            return false;
+        };
 
         for (piece, mask) in self.position.pieces() {
             for square in mask & friendly {
@@ -101,6 +165,150 @@ impl MoveGenerator {
         false
     }
 
+    /// Every legal move for the player up to move. Pawn moves to the
+    /// back rank are expanded into one move per promotion piece.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let friendly = self.position.color_mask(self.turn);
+        let Some(king) = self.king() else {
+            return moves;
+        };
+
+        for (piece, mask) in self.position.pieces() {
+            for from in mask & friendly {
+                for dest in self.destinations(piece, from, king) {
+                    // pawns moving to the enemy back rank must promote.
+                    if piece == Piece::Pawn && dest.rank() == (!self.turn).back_rank() {
+                        for promotion in Piece::PROMOTIONS {
+                            moves.push((from, dest, Some(promotion)));
+                        }
+                    } else {
+                        moves.push((from, dest, None));
+                    }
+                }
+            }
+        }
+
+        moves
+    }
+
+    /// Every legal (from, dest) pair for the player up to move. Unlike
+    /// 'legal_moves', pawn moves to the back rank are listed only once.
+    pub fn generate_all(&self) -> Vec<(Square, Square)> {
+        let mut buf = ArrayVec::new();
+        self.generate_all_into(&mut buf);
+        buf.to_vec()
+    }
+
+    /// Like 'generate_all', but the moves are written to a caller-provided buffer
+    /// instead of a new Vec, so a search can reuse one buffer per ply. The buffer
+    /// is cleared first. No legal position has more than 218 moves, so 256 is enough.
+    pub fn generate_all_into(&self, buf: &mut ArrayVec<(Square, Square), 256>) {
+        buf.clear();
+
+        let friendly = self.position.color_mask(self.turn);
+        let Some(king) = self.king() else {
+            return;
+        };
+
+        for (piece, mask) in self.position.pieces() {
+            for from in mask & friendly {
+                for dest in self.destinations(piece, from, king) {
+                    buf.push((from, dest));
+                }
+            }
+        }
+    }
+
+    /// The number of moves 'generate_all' would return, without allocating.
+    pub fn count_legal_moves(&self) -> usize {
+        let friendly = self.position.color_mask(self.turn);
+        let Some(king) = self.king() else {
+            return 0;
+        };
+
+        let mut count = 0;
+        for (piece, mask) in self.position.pieces() {
+            for from in mask & friendly {
+                count += self.destinations(piece, from, king).count() as usize;
+            }
+        }
+
+        count
+    }
+
+    /// Every legal move for the player up to move that puts the opponent in check.
+    pub fn checking_moves(&self) -> Vec<Move> {
+        let state = BoardState::new(self.position, self.fullmoves, self.turn, self.castle);
+
+        self.legal_moves()
+            .into_iter()
+            .filter(|(from, dest, promotion)| {
+                state
+                    .play_unchecked(*from, *dest, *promotion)
+                    .generator()
+                    .is_check()
+            })
+            .collect()
+    }
+
+    /// The mask of squares occupied by friendly pieces that, if moved, would
+    /// reveal an attack by a friendly slider on an enemy piece or the enemy king,
+    /// a.k.a. the candidates for a discovered attack or discovered check.
+    pub fn discovered_attackers(&self) -> Bitmask {
+        let mut result = Bitmask::EMPTY;
+        let blockers = self.position.occupied();
+        let friendly = self.position.color_mask(self.turn);
+        let enemies = self.position.color_mask(!self.turn);
+
+        // this is the pin detection in reverse, instead of enemy sliders
+        // looking at our king through one of our pieces, it is our sliders
+        // looking at an enemy piece through one of our pieces.
+        for (sliders, rays) in [
+            (self.position.diagonal_sliders(self.turn), &BISHOP),
+            (self.position.orthogonal_sliders(self.turn), &ROOK),
+        ] {
+            for slider in sliders {
+                for target in enemies & Bitmask(rays[slider as usize]) {
+                    let blocking = blockers & Bitmask(BETWEEN[slider as usize][target as usize]);
+
+                    // if the only piece in the way is friendly, moving it reveals the attack.
+                    if blocking.count() == 1 && blocking.intersects(friendly) {
+                        result |= blocking;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// The squares the piece can move to, like 'generate_internal', but with
+    /// castle listed once, as the king target square. Castle can also be
+    /// requested by dropping the king on the rook, which would be a duplicate.
+    fn destinations(&self, piece: Piece, from: Square, king: Square) -> Bitmask {
+        let mut dests = self.generate_internal(piece, from, king);
+
+        if piece == Piece::King {
+            for dir in [CastleDir::Short, CastleDir::Long] {
+                let target = self.castle.target_squares(self.turn, dir).0;
+                let rook = self.castle.rook_square(self.turn, dir);
+
+                // only a real castle can be requested on both squares, a plain
+                // king step onto the rook square is its own move and is kept.
+                if target != rook
+                    && self.castle.has_castle(self.turn, self.fullmoves, dir)
+                    && dests.has(target)
+                    && dests.has(rook)
+                {
+                    dests.remove(rook);
+                }
+            }
+        }
+
+        dests
+    }
+
     /// Private function for generating moves for a piece, assuming it
     /// exists in the position at the square and with the color.
     fn generate_internal(&self, piece: Piece, square: Square, king: Square) -> Bitmask {
@@ -141,12 +349,8 @@ impl MoveGenerator {
                                 0 => specials.set(en_passant_sq),
                                 // if there is 1 check, and the capture square is the checking piece,
                                 // assume en passant is valid.
-                                1 if self.checking.has(capture_sq) => {
-                                    // en passant is only valid if the pawn is not pinned.
-                                    if !self.pinned.has(square) {
-                                        specials.set(en_passant_sq)
-                                    }
-                                }
+                                // pins were already covered by the discovered check test.
+                                1 if self.checking.has(capture_sq) => specials.set(en_passant_sq),
                                 // if there is 1 check, and it is not the capture square,
                                 // then add the en passant square to the capturable so the
                                 // check and pin detection can handle the result.
@@ -183,7 +387,7 @@ impl MoveGenerator {
                                 .castle
                                 .check_mask(king, self.turn, dir)
                                 .intersects(self.defense)
-                                && self
+                                && !self
                                     .castle
                                     .block_mask(king, self.turn, dir)
                                     .intersects(blockers)
@@ -206,9 +410,12 @@ impl MoveGenerator {
 
         // Moves must capture checking pieces
         // or block a checking peices' sightline
-        // to the king.
-        for checking in self.checking {
-            attacks &= Bitmask(BETWEEN[king as usize][checking as usize]).with(checking)
+        // to the king. The king itself escapes check
+        // by stepping off the defended squares instead.
+        if piece != Piece::King {
+            for checking in self.checking {
+                attacks &= Bitmask(BETWEEN[king as usize][checking as usize]).with(checking)
+            }
         }
 
         // If the piece is pinned, then only moves that maintain the
@@ -224,11 +431,23 @@ impl MoveGenerator {
         attacks | specials
     }
 
-    /// Get the square the king is on.
-    fn king(&self) -> Square {
-        (self.position.kings() & self.position.color_mask(self.turn))
-            .first()
-            .expect("MoveGenerator expects the position to have a king.")
+    /// The squares a piece at the square would capture on when moving to them, which are
+    /// the enemy pieces, plus the en passant square if the piece is a pawn.
+    fn capture_targets(&self, square: Square) -> Bitmask {
+        let mut targets = self.position.color_mask(!self.turn);
+
+        if let (Some(en_passant_sq), Some((_, Piece::Pawn))) =
+            (self.position.en_passant(), self.position.piece_at(square))
+        {
+            targets.set(en_passant_sq);
+        }
+
+        targets
+    }
+
+    /// Get the square the king is on, if there is one.
+    fn king(&self) -> Option<Square> {
+        (self.position.kings() & self.position.color_mask(self.turn)).first()
     }
 }
 
@@ -236,13 +455,11 @@ impl MoveGenerator {
 fn compute_defense_mask(pos: &Position, turn: Color) -> Bitmask {
     let mut defense = Bitmask::EMPTY;
 
-    // the king square of the turn color.
-    let king = (pos.kings() & pos.color_mask(turn))
-        .first()
-        .expect("MoveGenerator::new() expects the position to have a king.");
+    // the king of the turn color, which sliders can see through.
+    let king = pos.kings() & pos.color_mask(turn);
 
     let friendly = pos.color_mask(turn);
-    let blockers = pos.occupied().without(king);
+    let blockers = pos.occupied() & !king;
 
     // Compute the squares defended by the enemy team.
     for (piece, mask) in pos.pieces() {
@@ -262,10 +479,11 @@ fn compute_pinned_and_checking_masks(pos: &Position, turn: Color) -> (Bitmask, B
     let mut pinned = Bitmask::EMPTY;
     let mut checking = Bitmask::EMPTY;
 
-    // the king square of the turn color.
-    let king = (pos.kings() & pos.color_mask(turn))
-        .first()
-        .expect("MoveGenerator::new() expects the position to have a king.");
+    // the king square of the turn color, without
+    // a king there is nothing to pin or check.
+    let Some(king) = (pos.kings() & pos.color_mask(turn)).first() else {
+        return (pinned, checking);
+    };
 
     // all occupied squares, which block slides.
     let blockers = pos.occupied();
@@ -303,8 +521,8 @@ fn compute_pinned_and_checking_masks(pos: &Position, turn: Color) -> (Bitmask, B
         // if there is one square blocking the diagonal sliders' line
         // of sight to the king, and the color of that piece is
         // the same as the king, then the square is pinned.
-        if blocking.count() == 1 && friendly.has(square) {
-            pinned.set(square);
+        if blocking.count() == 1 && blocking.intersects(friendly) {
+            pinned |= blocking;
         }
     }
 
@@ -346,24 +564,20 @@ fn en_passant_would_move_into_discovered_check(
         .without(square)
         .without(capture_sq);
 
-    // If the capture sq and the king share an orthogonal,
-    // then it is possible for en passant to result in a discovered check,
-    // which is invalid. The same is true if they share a diagonal.
-    for square in if capture_sq.shares_orthogonal(king) {
-        pos.orthogonal_sliders(!turn) & Bitmask(cached::ROOK[king as usize])
-    } else if epsq.shares_diagonal(king) {
-        pos.diagonal_sliders(!turn) & Bitmask(cached::BISHOP[king as usize])
-    } else {
-        return true;
-    } {
+    // en passant removes two pieces from their squares at once, so it can
+    // open a line to the king that no pin detection would have caught, like
+    // both pawns standing between the king and a rook on the same rank.
+    for square in (pos.orthogonal_sliders(!turn) & Bitmask(cached::ROOK[king as usize]))
+        | (pos.diagonal_sliders(!turn) & Bitmask(cached::BISHOP[king as usize]))
+    {
         // if no squares between the slider and the king are occupied, then en passant would
         // move into discovered check.
         if !(Bitmask(cached::BETWEEN[king as usize][square as usize]).intersects(blockers)) {
-            return false;
+            return true;
         }
     }
 
-    true
+    false
 }
 
 #[cfg(test)]
@@ -381,4 +595,261 @@ mod tests {
 
         assert_eq!(generator.generate(Square::D5), Square::E6.mask());
     }
+
+    #[test]
+    fn generate_king_in_check() {
+        // the king can't block or capture the rook, so it has to step off the first rank.
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/8/r3K3 w - - 0 1").unwrap();
+        let generator = board.generator();
+
+        assert!(generator.is_check());
+        assert_eq!(
+            generator.generate(Square::E1),
+            Square::D2.mask() | Square::E2.mask() | Square::F2.mask()
+        );
+    }
+
+    #[test]
+    fn generate_pinned() {
+        // the knight can't leave the file between the king and the rook.
+        let board = BoardState::from_fen("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+        assert_eq!(board.generator().generate(Square::E2), Bitmask::EMPTY);
+
+        // a pinned rook can still move along the pin, up to capturing the pinner.
+        let board = BoardState::from_fen("4r1k1/8/8/8/8/8/4R3/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.generator().generate(Square::E2),
+            Bitmask::EMPTY
+                .with_file(crate::square::File::E)
+                .without(Square::E1)
+                .without(Square::E2)
+        );
+    }
+
+    #[test]
+    fn generate_en_passant() {
+        let board = BoardState::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        assert_eq!(
+            board.generator().generate(Square::E5),
+            Square::D6.mask() | Square::E6.mask()
+        );
+
+        // taking en passant would clear the rank between the king and the rook.
+        let board = BoardState::from_fen("8/8/8/K2pP2r/8/8/8/7k w - d6 0 1").unwrap();
+        assert_eq!(board.generator().generate(Square::E5), Square::E6.mask());
+    }
+
+    #[test]
+    fn generate_captures() {
+        // the knight on d5 can take the rook on c7 or the pawn on e7, and the
+        // pawn on e5 can take en passant on f6, which is quiet for the knight.
+        let board = BoardState::from_fen("4k3/2r1p3/8/3NPp2/8/8/8/4K3 w - f6 0 1").unwrap();
+        let generator = board.generator();
+
+        assert_eq!(
+            generator.generate_captures(Square::D5),
+            Square::C7.mask().with(Square::E7)
+        );
+        assert!(generator.generate_quiet(Square::D5).has(Square::F6));
+        assert_eq!(generator.generate_captures(Square::E5), Square::F6.mask());
+        assert_eq!(generator.generate_captures(Square::E1), Bitmask::EMPTY);
+
+        for square in [Square::D5, Square::E5, Square::E1] {
+            let captures = generator.generate_captures(square);
+            let quiet = generator.generate_quiet(square);
+
+            assert!(!captures.intersects(quiet));
+            assert_eq!(captures | quiet, generator.generate(square));
+        }
+    }
+
+    #[test]
+    fn generate_all() {
+        let generator = BoardState::default().generator();
+
+        assert_eq!(generator.generate_all().len(), 20);
+        assert_eq!(generator.count_legal_moves(), 20);
+
+        // castle is listed once, even though dropping the king on the rook also castles.
+        let generator = BoardState::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1")
+            .unwrap()
+            .generator();
+
+        assert!(generator.generate_all().contains(&(Square::E1, Square::G1)));
+        assert!(!generator.generate_all().contains(&(Square::E1, Square::H1)));
+        assert_eq!(
+            generator.count_legal_moves(),
+            generator.generate_all().len()
+        );
+
+        // without castle rights, the king can step onto the corner next to g1.
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/6K1/8 w - - 0 1").unwrap();
+        let generator = board.generator();
+
+        assert!(generator.generate_all().contains(&(Square::G2, Square::H1)));
+        assert_eq!(generator.generate_all().len(), 8);
+        assert_eq!(generator.count_legal_moves(), 8);
+        assert_eq!(board.legal_moves().len(), 8);
+    }
+
+    #[test]
+    fn generate_all_into() {
+        let generator = BoardState::default().generator();
+        let mut buf = ArrayVec::new();
+
+        generator.generate_all_into(&mut buf);
+        assert_eq!(buf.len(), 20);
+        assert_eq!(buf.to_vec(), generator.generate_all());
+
+        // the buffer is cleared before it is filled again.
+        generator.generate_all_into(&mut buf);
+        assert_eq!(buf.len(), 20);
+    }
+
+    #[test]
+    fn generate_all_double_check() {
+        // in double check, only the king can move.
+        let board = BoardState::from_fen("4k3/8/5N2/8/8/8/1q6/4RK2 b - - 0 1").unwrap();
+        let generator = board.generator();
+        let moves = generator.generate_all();
+
+        assert!(!moves.is_empty());
+        assert!(moves.iter().all(|(from, _)| *from == Square::E8));
+        assert_eq!(generator.count_legal_moves(), moves.len());
+    }
+
+    #[test]
+    fn double_check() {
+        // the rook on e1 and the knight on f6 both check the king on e8.
+        let board = BoardState::from_fen("4k3/8/5N2/8/8/8/8/4RK2 b - - 0 1").unwrap();
+        let generator = board.generator();
+
+        assert_eq!(generator.checkers().count(), 2);
+        assert_eq!(generator.checker_count(), 2);
+        assert_eq!(generator.checkers(), Square::E1.mask().with(Square::F6));
+        assert!(generator.attacked_squares().has(Square::E7));
+        assert!(generator.attacked_squares().has(Square::D7));
+    }
+
+    #[test]
+    fn pinned() {
+        // the knight on e5 is pinned by the rook on e8.
+        let board = BoardState::from_fen("4r1k1/8/8/4N3/8/8/8/4K3 w - - 0 1").unwrap();
+        let generator = board.generator();
+
+        assert_eq!(generator.pinned(), Square::E5.mask());
+        assert_eq!(generator.checker_count(), 0);
+        assert_eq!(generator.generate(Square::E5), Bitmask::EMPTY);
+    }
+
+    #[test]
+    fn discovered_attackers() {
+        // the knight on c3 is blocking the bishop on a1 from the king on h8.
+        let board = BoardState::from_fen("7k/8/8/8/8/2N5/8/B3K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.generator().discovered_attackers(), Square::C3.mask());
+    }
+
+    #[test]
+    fn discovered_attackers_enemy_blocker() {
+        // the rook is blocked by an enemy pawn, so there is nothing to discover.
+        let board = BoardState::from_fen("4k3/8/4p3/8/8/8/8/4R1K1 w - - 0 1").unwrap();
+
+        assert_eq!(board.generator().discovered_attackers(), Bitmask::EMPTY);
+    }
+
+    #[test]
+    fn generate_empty_board() {
+        let board = BoardState::from_fen("8/8/8/8/8/8/8/8 w - - 0 1").unwrap();
+        let generator = board.generator();
+
+        assert!(!generator.is_check());
+        assert!(!generator.has_any_moves());
+        assert!(generator.legal_moves().is_empty());
+        assert_eq!(generator.generate(Square::E4), Bitmask::EMPTY);
+    }
+
+    #[test]
+    fn generate_missing_king() {
+        // pieces without a king of their own can't move.
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/8/R7 w - - 0 1").unwrap();
+        let generator = board.generator();
+
+        assert!(!generator.has_any_moves());
+        assert_eq!(generator.generate(Square::A1), Bitmask::EMPTY);
+    }
+
+    #[test]
+    fn castle_blocked_kingside() {
+        // the bishop on f1 blocks O-O, but the queenside is clear.
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/8/R3KB1R w KQ - 0 1").unwrap();
+        let moves = board.generator().generate(Square::E1);
+
+        assert!(moves.has(Square::C1));
+        assert!(moves.has(Square::A1));
+        assert!(!moves.has(Square::G1));
+        assert!(!moves.has(Square::H1));
+    }
+
+    #[test]
+    fn castle_blocked_queenside() {
+        // the knight on b1 blocks O-O-O, even though the king never crosses b1.
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/8/RN2K2R w KQ - 0 1").unwrap();
+        let moves = board.generator().generate(Square::E1);
+
+        assert!(moves.has(Square::G1));
+        assert!(moves.has(Square::H1));
+        assert!(!moves.has(Square::C1));
+        assert!(!moves.has(Square::A1));
+    }
+
+    #[test]
+    fn checking_moves() {
+        // the rook can check from e2 or h8, and the king has no checks.
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/7R/K7 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.generator().checking_moves(),
+            vec![
+                (Square::H2, Square::E2, None),
+                (Square::H2, Square::H8, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn order_captures() {
+        let board = BoardState::from_fen("7k/8/p7/4q3/3P4/8/8/Q3R2K w - - 0 1").unwrap();
+        let generator = board.generator();
+
+        let mut moves = vec![
+            (Square::H1, Square::G1),
+            (Square::A1, Square::A6),
+            (Square::D4, Square::E5),
+            (Square::A1, Square::A2),
+            (Square::E1, Square::E5),
+        ];
+        generator.order_captures(&mut moves);
+
+        // PxQ wins the most, RxQ still wins material, QxP risks the queen.
+        assert_eq!(
+            moves,
+            [
+                (Square::D4, Square::E5),
+                (Square::E1, Square::E5),
+                (Square::A1, Square::A6),
+                (Square::H1, Square::G1),
+                (Square::A1, Square::A2),
+            ]
+        );
+    }
+
+    #[test]
+    fn order_captures_en_passant() {
+        let board = BoardState::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let mut moves = board.generator().generate_all();
+        board.generator().order_captures(&mut moves);
+
+        assert_eq!(moves[0], (Square::E5, Square::D6));
+    }
 }
@@ -2,24 +2,33 @@ mod bitmask;
 mod cached;
 mod castle;
 mod color;
+mod epd;
 mod fen;
 mod game;
 mod generator;
+mod packed;
+mod perft;
 mod pgn;
 mod piece;
 mod position;
 mod record;
 mod square;
 mod state;
+mod transposition;
 
 pub use bitmask::Bitmask;
 pub use castle::{CastleDir, CastleRights};
 pub use color::Color;
+pub use epd::EpdParser;
 pub use fen::{FenParseError, FenParser};
-pub use game::ChessGame;
-pub use generator::MoveGenerator;
-pub use piece::Piece;
+pub use game::{ChessGame, DrawReason, GameResult};
+pub use generator::{Move, MoveGenerator};
+pub use packed::PackedMove;
+pub use perft::{perft, perft_divide};
+pub use pgn::{PgnMove, PgnParseError, PgnParser, PgnReader};
+pub use piece::{Piece, PieceValues};
 pub use position::{BoardChange, Position};
-pub use record::{MoveRecord, MoveString};
-pub use square::{File, Rank, Square};
-pub use state::BoardState;
+pub use record::{MoveRecord, MoveString, RecordedMove};
+pub use square::{File, Rank, Square, SquareParseError};
+pub use state::{BoardState, MoveError, MoveKind, SanParseError, UciParseError};
+pub use transposition::TranspositionTable;
@@ -0,0 +1,197 @@
+use crate::castle::CastleDir;
+use crate::generator::Move;
+use crate::piece::Piece;
+use crate::square::Square;
+use crate::state::BoardState;
+
+/// A move packed into 16 bits, for memory-efficient move lists
+/// and transposition table entries. The first 6 bits are the from
+/// square, the next 6 are the destination square, and the last 4 are flags.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct PackedMove(u16);
+
+impl PackedMove {
+    /// The move doesn't capture, promote, or do anything special.
+    pub const QUIET: u8 = 0b0000;
+    /// A pawn moving two squares forward.
+    pub const DOUBLE_PUSH: u8 = 0b0001;
+    /// The king castling, in either direction.
+    pub const CASTLE: u8 = 0b0010;
+    /// The move captures a piece.
+    pub const CAPTURE: u8 = 0b0100;
+    /// A pawn capturing en passant.
+    pub const EN_PASSANT: u8 = 0b0101;
+    /// The move promotes a pawn, where the low 2 bits
+    /// are the piece, and the capture bit may also be set.
+    pub const PROMOTION: u8 = 0b1000;
+
+    /// Pack a move without any information about the position, so
+    /// the only flags that can be set are the promotion flags.
+    pub fn new(mv: Move) -> Self {
+        let (from, dest, promotion) = mv;
+
+        Self::from_parts(
+            from,
+            dest,
+            promotion.map(promotion_flags).unwrap_or(Self::QUIET),
+        )
+    }
+
+    /// Pack a move, using the board state the move is played in to
+    /// set the capture, en passant, castle, and double push flags.
+    pub fn from_state(state: &BoardState, mv: Move) -> Self {
+        let (from, dest, promotion) = mv;
+        let position = state.position();
+
+        let flags = match position.piece_at(from) {
+            Some((color, Piece::King))
+                if [CastleDir::Short, CastleDir::Long].into_iter().any(|dir| {
+                    state.castle().has_castle(color, state.fullmoves(), dir)
+                        && state.castle().castle_play_mask(color, dir).has(dest)
+                }) =>
+            {
+                Self::CASTLE
+            }
+            Some((_, Piece::Pawn)) if position.en_passant() == Some(dest) => Self::EN_PASSANT,
+            Some((_, Piece::Pawn)) if (from.rank() as i8 - dest.rank() as i8).abs() == 2 => {
+                Self::DOUBLE_PUSH
+            }
+            _ => {
+                let capture = if position.piece_at(dest).is_some() {
+                    Self::CAPTURE
+                } else {
+                    Self::QUIET
+                };
+
+                promotion.map(promotion_flags).unwrap_or(Self::QUIET) | capture
+            }
+        };
+
+        Self::from_parts(from, dest, flags)
+    }
+
+    /// Unpack the move into the (from, dest, promotion) format.
+    pub fn unpack(&self) -> Move {
+        (self.from(), self.dest(), self.promotion())
+    }
+
+    /// The square the piece is moving from.
+    pub fn from(&self) -> Square {
+        Square::try_idx((self.0 & 0b111111) as u8).unwrap()
+    }
+
+    /// The square the piece is moving to.
+    pub fn dest(&self) -> Square {
+        Square::try_idx(((self.0 >> 6) & 0b111111) as u8).unwrap()
+    }
+
+    /// The 4 flag bits of the move.
+    pub fn flags(&self) -> u8 {
+        (self.0 >> 12) as u8
+    }
+
+    /// The piece the move promotes to, if any.
+    pub fn promotion(&self) -> Option<Piece> {
+        if self.is_promotion() {
+            Some(match self.flags() & 0b11 {
+                0 => Piece::Knight,
+                1 => Piece::Bishop,
+                2 => Piece::Rook,
+                _ => Piece::Queen,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Whether the move promotes a pawn.
+    pub fn is_promotion(&self) -> bool {
+        self.flags() & Self::PROMOTION != 0
+    }
+
+    /// Whether the move captures a piece, including en passant.
+    pub fn is_capture(&self) -> bool {
+        self.flags() & Self::CAPTURE != 0
+    }
+
+    /// Whether the move is a pawn capturing en passant.
+    pub fn is_en_passant(&self) -> bool {
+        self.flags() == Self::EN_PASSANT
+    }
+
+    /// Whether the move is the king castling.
+    pub fn is_castle(&self) -> bool {
+        self.flags() == Self::CASTLE
+    }
+
+    /// Whether the move is a pawn moving two squares forward.
+    pub fn is_double_push(&self) -> bool {
+        self.flags() == Self::DOUBLE_PUSH
+    }
+
+    fn from_parts(from: Square, dest: Square, flags: u8) -> Self {
+        Self(from as u16 | (dest as u16) << 6 | (flags as u16) << 12)
+    }
+}
+
+impl From<Move> for PackedMove {
+    fn from(value: Move) -> Self {
+        Self::new(value)
+    }
+}
+
+/// The flags for a promotion to the piece.
+fn promotion_flags(piece: Piece) -> u8 {
+    PackedMove::PROMOTION
+        | match piece {
+            Piece::Knight => 0,
+            Piece::Bishop => 1,
+            Piece::Rook => 2,
+            _ => 3,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_quiet() {
+        let mv = (Square::G1, Square::F3, None);
+        let packed = PackedMove::from_state(&BoardState::default(), mv);
+
+        assert_eq!(packed.unpack(), mv);
+        assert_eq!(packed.flags(), PackedMove::QUIET);
+        assert_eq!(PackedMove::new(mv), packed);
+    }
+
+    #[test]
+    fn packed_promotion() {
+        let board = BoardState::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        for promotion in Piece::PROMOTIONS {
+            let mv = (Square::A7, Square::A8, Some(promotion));
+            let packed = PackedMove::new(mv);
+            assert_eq!(packed.unpack(), mv);
+            assert!(packed.is_promotion());
+            assert!(!packed.is_capture());
+
+            let mv = (Square::A7, Square::B8, Some(promotion));
+            let packed = PackedMove::from_state(&board, mv);
+            assert_eq!(packed.unpack(), mv);
+            assert!(packed.is_promotion());
+            assert!(packed.is_capture());
+        }
+    }
+
+    #[test]
+    fn packed_castle() {
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let mv = (Square::E1, Square::G1, None);
+        let packed = PackedMove::from_state(&board, mv);
+
+        assert_eq!(packed.unpack(), mv);
+        assert!(packed.is_castle());
+        assert!(!packed.is_capture());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::record::MoveString;
+use crate::state::BoardState;
+
+/// Count the leaf nodes of the move tree to the given depth, the standard
+/// way of validating a move generator against known results.
+pub fn perft(state: &BoardState, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = state.legal_moves();
+
+    // at the last ply every legal move is a leaf, so
+    // there is no need to play them out.
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    moves
+        .into_iter()
+        .map(|(from, dest, promote)| perft(&state.play_unchecked(from, dest, promote), depth - 1))
+        .sum()
+}
+
+/// Perft split by the root moves, as (uci, nodes). Comparing this against
+/// another engine narrows a mismatched count down to the move causing it.
+pub fn perft_divide(state: &BoardState, depth: u32) -> Vec<(MoveString, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+
+    state
+        .legal_moves()
+        .into_iter()
+        .map(|(from, dest, promote)| {
+            let uci = MoveString::from(&state.to_uci(from, dest, promote))
+                .expect("UCI moves are at most 5 characters!");
+
+            (
+                uci,
+                perft(&state.play_unchecked(from, dest, promote), depth - 1),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+    #[test]
+    fn perft_start() {
+        let board = BoardState::default();
+
+        assert_eq!(perft(&board, 0), 1);
+        assert_eq!(perft(&board, 1), 20);
+        assert_eq!(perft(&board, 2), 400);
+        assert_eq!(perft(&board, 3), 8902);
+    }
+
+    #[test]
+    fn perft_kiwipete() {
+        let board = BoardState::from_fen(KIWIPETE).unwrap();
+
+        assert_eq!(perft(&board, 1), 48);
+        assert_eq!(perft(&board, 2), 2039);
+        assert_eq!(perft(&board, 3), 97862);
+    }
+
+    #[test]
+    fn perft_en_passant_pins() {
+        // the third position from the chess programming wiki,
+        // full of en passant captures that expose the king.
+        let board = BoardState::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+
+        assert_eq!(perft(&board, 1), 14);
+        assert_eq!(perft(&board, 2), 191);
+        assert_eq!(perft(&board, 3), 2812);
+        assert_eq!(perft(&board, 4), 43238);
+    }
+
+    #[test]
+    fn perft_castle_rights() {
+        // black castle rights with white pawns ready to promote on both wings.
+        let board = BoardState::from_fen(
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        )
+        .unwrap();
+
+        assert_eq!(perft(&board, 1), 6);
+        assert_eq!(perft(&board, 2), 264);
+        assert_eq!(perft(&board, 3), 9467);
+    }
+
+    #[test]
+    fn perft_promotions() {
+        let board =
+            BoardState::from_fen("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8")
+                .unwrap();
+
+        assert_eq!(perft(&board, 1), 44);
+        assert_eq!(perft(&board, 2), 1486);
+        assert_eq!(perft(&board, 3), 62379);
+    }
+
+    #[test]
+    fn perft_lone_king() {
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/6K1/8 w - - 0 1").unwrap();
+
+        assert_eq!(perft(&board, 1), 8);
+    }
+
+    #[test]
+    fn perft_chess960() {
+        // the kingside rook already stands on the king's castle target square.
+        let board =
+            BoardState::from_fen("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9")
+                .unwrap();
+
+        assert_eq!(perft(&board, 1), 20);
+        assert_eq!(perft(&board, 2), 479);
+        assert_eq!(perft(&board, 3), 10471);
+        assert_eq!(perft(&board, 4), 273318);
+    }
+
+    #[test]
+    fn perft_divide_sums_to_perft() {
+        let board = BoardState::from_fen(KIWIPETE).unwrap();
+        let divide = perft_divide(&board, 2);
+
+        assert_eq!(divide.len(), 48);
+        assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 2039);
+        assert!(divide.iter().any(|(uci, _)| uci.as_str() == "e1g1"));
+    }
+}
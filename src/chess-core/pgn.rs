@@ -1,17 +1,634 @@
 use std::collections::HashMap;
+use std::io::BufRead;
 
+use crate::fen::FenParseError;
+use crate::game::ChessGame;
+use crate::state::{BoardState, SanParseError};
+
+/// The tokens that can end the movetext of a game.
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+/// PGN is a list of '[Name "value"]' tag pairs, followed by the movetext,
+/// the moves of the game in SAN with move numbers, ending in the result.
 #[derive(Clone, Debug)]
 pub struct PgnParser<'a> {
     tags: HashMap<&'a str, &'a str>,
+    movetext: &'a str,
 }
 
 impl<'a> PgnParser<'a> {
-    pub fn new(pgn: &'a str) -> Result<PgnParseError, Self> {
-        todo!()
+    /// Parse the tag pairs of a PGN and split off the movetext.
+    /// This function will return an error if a tag pair is malformed.
+    /// The moves are not checked until 'game' is called.
+    pub fn new(pgn: &'a str) -> Result<Self, PgnParseError> {
+        let mut tags = HashMap::new();
+        let mut rest = pgn.trim_start();
+
+        while let Some(tag) = rest.strip_prefix('[') {
+            let (name, value, after) = parse_tag(tag)?;
+            tags.insert(name, value);
+            rest = after.trim_start();
+        }
+
+        Ok(Self {
+            tags,
+            movetext: rest.trim_end(),
+        })
+    }
+
+    /// Get the value of the tag with the name, if there is one.
+    /// Escaped quotes and backslashes in the value are left as-is.
+    pub fn tag(&self, name: &str) -> Option<&'a str> {
+        self.tags.get(name).copied()
+    }
+
+    /// All the tag pairs in the PGN, mapping each name to its value.
+    pub fn tags(&self) -> &HashMap<&'a str, &'a str> {
+        &self.tags
+    }
+
+    /// The movetext of the PGN, everything after the tag pairs.
+    pub fn movetext(&self) -> &'a str {
+        self.movetext
+    }
+
+    /// The result token at the end of the movetext, one of
+    /// '1-0', '0-1', '1/2-1/2', or '*', if there is one.
+    pub fn result(&self) -> Option<&'a str> {
+        self.tokens()
+            .ok()?
+            .into_iter()
+            .find_map(|token| match token {
+                Token::Result(result) => Some(result),
+                _ => None,
+            })
+    }
+
+    /// The moves of the mainline, each with the comments and NAGs that follow it.
+    /// Move suffixes like '!' or '?!' are turned into their NAGs, and variations
+    /// are skipped. Comments before the first move are dropped.
+    pub fn moves(&self) -> Result<Vec<PgnMove<'a>>, PgnParseError> {
+        let mut moves: Vec<PgnMove<'a>> = Vec::new();
+
+        for token in self.tokens()? {
+            match token {
+                Token::Move(san) => {
+                    let (san, nag) = strip_suffix(san);
+
+                    moves.push(PgnMove {
+                        san,
+                        comments: Vec::new(),
+                        nags: nag.into_iter().collect(),
+                    });
+                }
+                Token::Comment(comment) => {
+                    if let Some(last) = moves.last_mut() {
+                        last.comments.push(comment);
+                    }
+                }
+                Token::Nag(nag) => {
+                    if let Some(last) = moves.last_mut() {
+                        last.nags.push(nag);
+                    }
+                }
+                Token::Result(_) => break,
+            }
+        }
+
+        Ok(moves)
+    }
+
+    /// Replay the movetext into a ChessGame, starting from the position in the
+    /// 'FEN' tag if there is one, or the standard starting position if not.
+    /// Move numbers are skipped, and the movetext ends at the result
+    /// token, or the end of the PGN if there isn't one.
+    pub fn game(&self) -> Result<ChessGame, PgnParseError> {
+        let mut game = ChessGame::new(self.first()?);
+
+        for mv in self.moves()? {
+            let (from, dest, promotion) = game
+                .last()
+                .parse_san(mv.san)
+                .map_err(PgnParseError::BadMove)?;
+
+            game.play(from, dest, promotion);
+
+            let recorded = game
+                .record_mut()
+                .last_mut()
+                .expect("a move was just played!");
+            recorded.set_nags(mv.nags);
+
+            if !mv.comments.is_empty() {
+                recorded.set_comment(Some(mv.comments.join(" ")));
+            }
+        }
+
+        Ok(game)
+    }
+
+    /// The state the game starts from. PGNs that don't start from the standard
+    /// position declare it with '[SetUp "1"]' and '[FEN "..."]', though the
+    /// 'SetUp' tag is often left out, so only an explicit "0" ignores the FEN.
+    pub fn first(&self) -> Result<BoardState, PgnParseError> {
+        match (self.tag("SetUp"), self.tag("FEN")) {
+            (Some("0"), _) | (_, None) => Ok(BoardState::default()),
+            (_, Some(fen)) => BoardState::from_fen(fen).map_err(PgnParseError::BadFen),
+        }
+    }
+
+    /// Split the movetext into tokens, skipping move numbers and variations.
+    fn tokens(&self) -> Result<Vec<Token<'a>>, PgnParseError> {
+        let mut tokens = Vec::new();
+        let mut rest = self.movetext.trim_start();
+
+        while let Some(c) = rest.chars().next() {
+            rest = match c {
+                '{' => {
+                    let end = rest.find('}').ok_or(PgnParseError::UnterminatedComment)?;
+                    tokens.push(Token::Comment(rest[1..end].trim()));
+                    &rest[end + 1..]
+                }
+                // comments to the end of the line.
+                ';' => {
+                    let end = rest.find('\n').unwrap_or(rest.len());
+                    tokens.push(Token::Comment(rest[1..end].trim()));
+                    &rest[end..]
+                }
+                '(' => skip_variation(rest)?,
+                '$' => {
+                    let end = rest[1..]
+                        .find(|c: char| !c.is_ascii_digit())
+                        .map_or(rest.len(), |end| end + 1);
+                    let nag = rest[1..end].parse().map_err(|_| PgnParseError::BadNag)?;
+                    tokens.push(Token::Nag(nag));
+                    &rest[end..]
+                }
+                _ => {
+                    let end = rest
+                        .find(|c: char| c.is_whitespace() || "{};()$".contains(c))
+                        .unwrap_or(rest.len());
+                    let word = strip_move_number(&rest[..end]);
+
+                    if RESULTS.contains(&word) {
+                        tokens.push(Token::Result(word));
+                    } else if !word.is_empty() {
+                        tokens.push(Token::Move(word));
+                    }
+
+                    // a stray ')' would otherwise never be consumed.
+                    &rest[end.max(c.len_utf8())..]
+                }
+            }
+            .trim_start();
+        }
+
+        Ok(tokens)
+    }
+}
+
+/// Reads the games of a PGN database one at a time, so the whole file never
+/// has to be in memory. Games are separated by the blank line after their
+/// movetext, or by the tag pairs of the next game.
+pub struct PgnReader<R: BufRead> {
+    reader: R,
+    /// A line read past the end of the previous game that belongs to the next.
+    pending: Option<String>,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            pending: None,
+        }
+    }
+
+    /// Read and parse the next game, or None if there are no games left.
+    pub fn next_game(&mut self) -> Option<Result<ChessGame, PgnParseError>> {
+        let mut pgn = self.pending.take().unwrap_or_default();
+        let mut in_movetext = false;
+
+        loop {
+            let mut line = String::new();
+
+            match self.reader.read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(error) => return Some(Err(PgnParseError::Io(error.kind()))),
+            }
+
+            let trimmed = line.trim();
+
+            if in_movetext {
+                if trimmed.is_empty() {
+                    break;
+                }
+
+                // the next game started without a blank line in between.
+                if trimmed.starts_with('[') {
+                    self.pending = Some(line);
+                    break;
+                }
+            } else if !trimmed.is_empty() && !trimmed.starts_with('[') {
+                in_movetext = true;
+            }
+
+            pgn.push_str(&line);
+        }
+
+        if pgn.trim().is_empty() {
+            return None;
+        }
+
+        Some(PgnParser::new(&pgn).and_then(|parser| parser.game()))
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<ChessGame, PgnParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_game()
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+/// A move of the mainline of a PGN, with the annotations that follow it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnMove<'a> {
+    /// The move, in SAN, without any '!' or '?' suffix.
+    pub san: &'a str,
+    /// The comments after the move, without the braces.
+    pub comments: Vec<&'a str>,
+    /// The Numeric Annotation Glyphs after the move, like 1 for '$1' or '!'.
+    pub nags: Vec<u8>,
+}
+
+/// A piece of the movetext.
+enum Token<'a> {
+    Move(&'a str),
+    Comment(&'a str),
+    Nag(u8),
+    Result(&'a str),
+}
+
+/// Skip the variation at the start of the string, including any variations
+/// nested inside it, returning everything after its closing ')'.
+fn skip_variation(movetext: &str) -> Result<&str, PgnParseError> {
+    let mut depth = 0;
+    let mut in_comment = false;
+
+    for (i, c) in movetext.char_indices() {
+        match c {
+            // parentheses inside a comment don't count.
+            '{' => in_comment = true,
+            '}' => in_comment = false,
+            '(' if !in_comment => depth += 1,
+            ')' if !in_comment => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Ok(&movetext[i + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(PgnParseError::UnterminatedVariation)
+}
+
+/// Split a move suffix like '!' or '?!' off the SAN, along with its NAG.
+fn strip_suffix(san: &str) -> (&str, Option<u8>) {
+    let stripped = san.trim_end_matches(['!', '?']);
+    let nag = match &san[stripped.len()..] {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None,
+    };
+
+    (stripped, nag)
+}
+
+/// Parse the tag pair at the start of the string, after the '[', returning
+/// the name, the value without its quotes, and everything after the ']'.
+fn parse_tag(tag: &str) -> Result<(&str, &str, &str), PgnParseError> {
+    let tag = tag.trim_start();
+    let name_end = tag
+        .find(|c: char| c.is_whitespace() || c == '"')
+        .ok_or(PgnParseError::BadTagFormat)?;
+    let (name, rest) = tag.split_at(name_end);
+
+    let value = rest
+        .trim_start()
+        .strip_prefix('"')
+        .ok_or(PgnParseError::BadTagFormat)?;
+
+    // find the closing quote, skipping over escaped characters.
+    let mut escaped = false;
+    let value_end = value
+        .char_indices()
+        .find(|(_, c)| match c {
+            _ if escaped => {
+                escaped = false;
+                false
+            }
+            '\\' => {
+                escaped = true;
+                false
+            }
+            c => *c == '"',
+        })
+        .map(|(i, _)| i)
+        .ok_or(PgnParseError::BadTagFormat)?;
+
+    let after = value[value_end + 1..]
+        .trim_start()
+        .strip_prefix(']')
+        .ok_or(PgnParseError::BadTagFormat)?;
+
+    if name.is_empty() {
+        return Err(PgnParseError::BadTagFormat);
+    }
+
+    Ok((name, &value[..value_end], after))
+}
+
+/// Remove a move number like '1.' or '12...' from the start of the token,
+/// which may be written with or without a space before the move.
+fn strip_move_number(token: &str) -> &str {
+    let digits = token.trim_start_matches(|c: char| c.is_ascii_digit());
+
+    // results like '1-0' also start with a digit, so only
+    // strip the digits if they are followed by a period.
+    if digits.len() < token.len() && digits.starts_with('.') {
+        digits.trim_start_matches('.')
+    } else {
+        token
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum PgnParseError {
     BadTagFormat,
+    BadFen(FenParseError),
+    BadMove(SanParseError),
+    BadNag,
+    UnterminatedComment,
+    UnterminatedVariation,
+    Io(std::io::ErrorKind),
+}
+
+impl std::fmt::Display for PgnParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::BadTagFormat => "tag pair malformed, expected [Name \"value\"]",
+            Self::BadFen(_) => "FEN tag is not a valid position",
+            Self::BadMove(_) => "movetext contains a malformed or illegal move",
+            Self::BadNag => "NAG must be '$' followed by a number from 0 to 255",
+            Self::UnterminatedComment => "comment is missing its closing '}'",
+            Self::UnterminatedVariation => "variation is missing its closing ')'",
+            Self::Io(_) => "failed to read the pgn",
+        };
+
+        write!(f, "{message}")
+    }
+}
+
+impl std::error::Error for PgnParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameResult;
+    use crate::record::RecordedMove;
+    use crate::square::Square;
+
+    const PGN: &str = r#"[Event "Casual Game"]
+[Site "London"]
+[Date "1851.06.21"]
+[White "Anderssen, Adolf"]
+[Black "Kieseritzky, Lionel"]
+[Result "1-0"]
+
+1.e4 e5 2.f4 exf4 3.Bc4 Qh4+ 4.Kf1 b5 5.Bxb5 Nf6 6.Nf3 Qh6 7.d3 Nh5 8.Nh4 Qg5
+9.Nf5 c6 10.g4 Nf6 11.Rg1 cxb5 12.h4 Qg6 13.h5 Qg5 14.Qf3 Ng8 15.Bxf4 Qf6
+16.Nc3 Bc5 17.Nd5 Qxb2 18.Bd6 Bxg1 19.e5 Qxa1+ 20.Ke2 Na6 21.Nxg7+ Kd8
+22.Qf6+ Nxf6 23.Be7# 1-0"#;
+
+    #[test]
+    fn tags() {
+        let pgn = PgnParser::new(PGN).unwrap();
+
+        assert_eq!(pgn.tag("White"), Some("Anderssen, Adolf"));
+        assert_eq!(pgn.tag("Black"), Some("Kieseritzky, Lionel"));
+        assert_eq!(pgn.tag("Round"), None);
+        assert_eq!(pgn.tags().len(), 6);
+        assert_eq!(pgn.result(), Some("1-0"));
+    }
+
+    #[test]
+    fn game() {
+        let game = PgnParser::new(PGN).unwrap().game().unwrap();
+
+        // 23 moves for white, 22 for black, plus the starting position.
+        assert_eq!(game.len(), 46);
+        assert_eq!(game.record().index(0).unwrap().notation().as_str(), "e4");
+        assert_eq!(
+            game.record().last(),
+            Some(&RecordedMove::new(
+                Square::D6,
+                Square::E7,
+                "Be7#".parse().unwrap()
+            ))
+        );
+        assert!(game.last().is_checkmate());
+    }
+
+    #[test]
+    fn game_move_numbers() {
+        // move numbers may be separated from the move, and black
+        // moves may have their own number with an ellipsis.
+        let pgn = PgnParser::new("1. e4 1... e5 2. Nf3 *").unwrap();
+
+        assert_eq!(pgn.result(), Some("*"));
+        assert_eq!(pgn.game().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn bad_tags() {
+        for pgn in [
+            "[White Anderssen]",
+            "[White \"Anderssen\"",
+            "[\"Anderssen\"]",
+            "[White \"Anderssen]",
+        ] {
+            assert_eq!(PgnParser::new(pgn).err(), Some(PgnParseError::BadTagFormat));
+        }
+
+        // escaped quotes don't end the value.
+        let pgn = PgnParser::new(r#"[Event "The \"Immortal\" Game"]"#).unwrap();
+        assert_eq!(pgn.tag("Event"), Some(r#"The \"Immortal\" Game"#));
+    }
+
+    #[test]
+    fn bad_move() {
+        let pgn = PgnParser::new("1. e4 e5 2. Ke3").unwrap();
+
+        assert_eq!(
+            pgn.game().err(),
+            Some(PgnParseError::BadMove(SanParseError::IllegalMove))
+        );
+    }
+
+    #[test]
+    fn game_from_fen() {
+        // white mates on the back rank, starting from a custom position with black to move.
+        let pgn = PgnParser::new(
+            r#"[Event "Puzzle"]
+[SetUp "1"]
+[FEN "6k1/p4ppp/8/8/8/8/5PPP/3R2K1 b - - 0 30"]
+
+30... a6 31. Rd8# 1-0"#,
+        )
+        .unwrap();
+
+        let game = pgn.game().unwrap();
+        assert_eq!(game.first(), &pgn.first().unwrap());
+        assert_eq!(game.first().fullmoves(), 30);
+        assert_eq!(game.len(), 3);
+        assert_eq!(game.record().index(0).unwrap().notation().as_str(), "a6");
+        assert_eq!(game.record().last().unwrap().notation().as_str(), "Rd8#");
+        assert!(game.last().is_checkmate());
+    }
+
+    #[test]
+    fn game_bad_fen() {
+        let pgn = PgnParser::new("[SetUp \"1\"]\n[FEN \"not a fen\"]\n*").unwrap();
+        assert!(matches!(pgn.game(), Err(PgnParseError::BadFen(_))));
+
+        // SetUp "0" means the FEN tag doesn't apply.
+        let pgn = PgnParser::new("[SetUp \"0\"]\n[FEN \"not a fen\"]\n*").unwrap();
+        assert_eq!(pgn.first(), Ok(BoardState::default()));
+    }
+
+    #[test]
+    fn moves_annotated() {
+        let pgn = PgnParser::new(
+            "1. e4 {the king's pawn} e5 2. Nf3!? $14 (2. f4 {the King's Gambit} exf4 (2... d5)) \
+             Nc6 ; a comment to the end of the line\n3. Bb5 *",
+        )
+        .unwrap();
+
+        let moves = pgn.moves().unwrap();
+        let sans: Vec<&str> = moves.iter().map(|mv| mv.san).collect();
+
+        assert_eq!(sans, ["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+        assert_eq!(moves[0].comments, ["the king's pawn"]);
+        assert_eq!(moves[2].nags, [5, 14]);
+        assert!(moves[2].comments.is_empty());
+        assert_eq!(moves[3].comments, ["a comment to the end of the line"]);
+        assert_eq!(pgn.result(), Some("*"));
+        assert_eq!(pgn.game().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn game_annotated() {
+        let pgn = "1. e4! {Best by test} e5 2. f4 {The King's Gambit} {accepted?} exf4 $2 *";
+        let game = PgnParser::new(pgn).unwrap().game().unwrap();
+
+        let first = game.record().index(0).unwrap();
+        assert_eq!(first.comment(), Some("Best by test"));
+        assert_eq!(first.nags(), [1]);
+        assert_eq!(
+            game.record().index(2).unwrap().comment(),
+            Some("The King's Gambit accepted?")
+        );
+        assert_eq!(game.record().last().unwrap().nags(), [2]);
+
+        assert_eq!(
+            game.record().to_movetext(),
+            "1. e4 $1 {Best by test} 1... e5 2. f4 {The King's Gambit accepted?} 2... exf4 $2"
+        );
+    }
+
+    #[test]
+    fn moves_unterminated() {
+        let pgn = PgnParser::new("1. e4 {never closed").unwrap();
+        assert_eq!(pgn.moves(), Err(PgnParseError::UnterminatedComment));
+
+        let pgn = PgnParser::new("1. e4 (1. d4 d5").unwrap();
+        assert_eq!(pgn.moves(), Err(PgnParseError::UnterminatedVariation));
+
+        let pgn = PgnParser::new("1. e4 $300").unwrap();
+        assert_eq!(pgn.moves(), Err(PgnParseError::BadNag));
+    }
+
+    #[test]
+    fn reader() {
+        let database = r#"[Event "First"]
+[Result "1-0"]
+
+1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0
+
+[Event "Second"]
+[Result "0-1"]
+
+1. f3 e5
+2. g4 Qh4# 0-1
+[Event "Third"]
+
+1. d4 *
+"#;
+
+        let mut reader = PgnReader::new(database.as_bytes());
+
+        let game = reader.next_game().unwrap().unwrap();
+        assert_eq!(game.len(), 8);
+        assert_eq!(game.result(), Some(GameResult::WhiteWins));
+
+        // the movetext of the second game is split over two lines.
+        let game = reader.next_game().unwrap().unwrap();
+        assert_eq!(game.len(), 5);
+        assert_eq!(game.result(), Some(GameResult::BlackWins));
+
+        // the third game starts right after the second without a blank line.
+        let game = reader.next_game().unwrap().unwrap();
+        assert_eq!(game.len(), 2);
+
+        assert!(reader.next_game().is_none());
+    }
+
+    #[test]
+    fn reader_bad_game() {
+        let database = "1. e4 e5\n\n1. e5\n\n1. d4 d5\n";
+        let games: Vec<_> = PgnReader::new(database.as_bytes()).collect();
+
+        assert_eq!(games.len(), 3);
+        assert!(games[0].is_ok());
+        assert_eq!(
+            games[1].as_ref().err(),
+            Some(&PgnParseError::BadMove(SanParseError::IllegalMove))
+        );
+        assert!(games[2].is_ok());
+    }
+
+    #[test]
+    fn error_display() {
+        for error in [
+            PgnParseError::BadTagFormat,
+            PgnParseError::BadFen(FenParseError::MissingInfo),
+            PgnParseError::BadMove(SanParseError::BadFormat),
+            PgnParseError::BadNag,
+            PgnParseError::UnterminatedComment,
+            PgnParseError::UnterminatedVariation,
+            PgnParseError::Io(std::io::ErrorKind::UnexpectedEof),
+        ] {
+            assert!(!error.to_string().is_empty());
+        }
+    }
 }
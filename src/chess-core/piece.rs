@@ -14,12 +14,48 @@ pub enum Piece {
 }
 
 impl Piece {
+    /// Every piece type, in index order from Pawn to Queen.
+    pub const ALL: [Self; 6] = [
+        Self::Pawn,
+        Self::King,
+        Self::Rook,
+        Self::Knight,
+        Self::Bishop,
+        Self::Queen,
+    ];
+
+    /// The pieces a pawn may promote to, from most to least valuable.
+    pub const PROMOTIONS: [Self; 4] = [Self::Queen, Self::Rook, Self::Bishop, Self::Knight];
+
+    /// Iterate all piece types, in index order from Pawn to Queen.
+    pub fn iter() -> impl DoubleEndedIterator<Item = Self> {
+        Self::ALL.into_iter()
+    }
+
     /// The Index of the Piece.
     /// Associates with an index in 'position'.
     pub fn index(self) -> usize {
         self as usize
     }
 
+    /// The index of the mask for this piece type in 'Position::masks',
+    /// which is offset by 2 since the first two masks are the colors.
+    pub fn mask_index(self) -> usize {
+        self.index() + 2
+    }
+
+    /// The material weight of the piece in centipawns,
+    /// using the standard values in 'PieceValues::default()'.
+    pub fn value(self) -> i32 {
+        PieceValues::default().get(self)
+    }
+
+    /// The material weight of the piece in centipawns,
+    /// as configured in 'values'.
+    pub fn value_with(self, values: &PieceValues) -> i32 {
+        values.get(self)
+    }
+
     /// The ID of the piece, as a character.
     /// If the provided color is Color::White,
     /// then the result will be uppercase.
@@ -39,6 +75,29 @@ impl Piece {
         }
     }
 
+    /// The Unicode chess glyph of the piece, for example '♔' for
+    /// a white king or '♟' for a black pawn.
+    pub fn unicode(self, color: Color) -> char {
+        match color {
+            Color::White => match self {
+                Self::Pawn => '♙',
+                Self::King => '♔',
+                Self::Rook => '♖',
+                Self::Knight => '♘',
+                Self::Bishop => '♗',
+                Self::Queen => '♕',
+            },
+            Color::Black => match self {
+                Self::Pawn => '♟',
+                Self::King => '♚',
+                Self::Rook => '♜',
+                Self::Knight => '♞',
+                Self::Bishop => '♝',
+                Self::Queen => '♛',
+            },
+        }
+    }
+
     /// Convert an index 0-5 into a piece.
     pub fn from_index(index: usize) -> Option<Self> {
         Some(match index {
@@ -135,9 +194,13 @@ impl Piece {
                             moves.remove(one);
                         }
 
-                        // two square.
+                        // two square, only from the home rank and only
+                        // when both the one and two square pushes are empty.
                         if let Some(two) = one.try_offset(0, color.pawn_dir()) {
-                            if !moves.has(one) || blockers.has(one) || blockers.has(two) {
+                            if square.rank() != color.pawn_rank()
+                                || blockers.has(one)
+                                || blockers.has(two)
+                            {
                                 moves.remove(two);
                             }
                         }
@@ -151,6 +214,22 @@ impl Piece {
         }
     }
 
+    /// Whether the piece is a Knight or a Bishop.
+    pub fn is_minor(&self) -> bool {
+        matches!(self, Piece::Knight | Piece::Bishop)
+    }
+
+    /// Whether the piece is a Rook or a Queen.
+    pub fn is_major(&self) -> bool {
+        matches!(self, Piece::Rook | Piece::Queen)
+    }
+
+    /// Whether a pawn may promote to this piece,
+    /// which is any piece except a pawn or king.
+    pub fn is_promotable(&self) -> bool {
+        Self::PROMOTIONS.contains(self)
+    }
+
     /// Whether the piece is a Rook, Bishop, or a Queen.
     pub fn is_slider(&self) -> bool {
         match self {
@@ -192,8 +271,155 @@ impl Piece {
     }
 }
 
+/// Centipawn weights for each piece type, indexed by 'Piece::index'.
+/// Defaults to the standard values, but can be overridden
+/// to tune evaluation and exchange scoring.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PieceValues(pub [i32; 6]);
+
+impl PieceValues {
+    /// The value of 'piece' in this table.
+    pub fn get(&self, piece: Piece) -> i32 {
+        self.0[piece.index()]
+    }
+
+    /// Replace the value of 'piece' in this table.
+    pub fn set(&mut self, piece: Piece, value: i32) {
+        self.0[piece.index()] = value;
+    }
+
+    /// A copy of this table with the value of 'piece' replaced.
+    pub fn with(mut self, piece: Piece, value: i32) -> Self {
+        self.set(piece, value);
+        self
+    }
+}
+
+impl Default for PieceValues {
+    fn default() -> Self {
+        // pawn, king, rook, knight, bishop, queen
+        Self([100, 0, 500, 320, 330, 900])
+    }
+}
+
 type NearestFn = fn(Bitmask) -> Option<Square>;
 
 fn between(sq1: Square, sq2: Square) -> Bitmask {
     Bitmask(cached::BETWEEN[sq1 as usize][sq2 as usize])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::Position;
+
+    #[test]
+    fn mask_index() {
+        let position = Position::default();
+
+        assert_eq!(Piece::Pawn.mask_index(), 2);
+        assert_eq!(Piece::Queen.mask_index(), 7);
+        assert_eq!(
+            position.masks()[Piece::Queen.mask_index()],
+            position.queens()
+        );
+        assert_eq!(position.masks()[Piece::Pawn.mask_index()], position.pawns());
+    }
+
+    #[test]
+    fn pawn_double_push_blocked_intermediate() {
+        let white = Piece::Pawn
+            .moves(Square::E2, Square::E3.mask(), Color::White)
+            .1;
+        let black = Piece::Pawn
+            .moves(Square::E7, Square::E6.mask(), Color::Black)
+            .1;
+
+        assert_eq!(white, Bitmask::EMPTY);
+        assert_eq!(black, Bitmask::EMPTY);
+    }
+
+    #[test]
+    fn pawn_double_push_blocked_destination() {
+        let white = Piece::Pawn
+            .moves(Square::E2, Square::E4.mask(), Color::White)
+            .1;
+        let black = Piece::Pawn
+            .moves(Square::E7, Square::E5.mask(), Color::Black)
+            .1;
+
+        assert_eq!(white, Square::E3.mask());
+        assert_eq!(black, Square::E6.mask());
+    }
+
+    #[test]
+    fn pawn_double_push() {
+        let white = Piece::Pawn
+            .moves(Square::E2, Bitmask::EMPTY, Color::White)
+            .1;
+        let black = Piece::Pawn
+            .moves(Square::E7, Bitmask::EMPTY, Color::Black)
+            .1;
+        let advanced = Piece::Pawn
+            .moves(Square::E3, Bitmask::EMPTY, Color::White)
+            .1;
+
+        assert_eq!(white, Square::E3.mask().with(Square::E4));
+        assert_eq!(black, Square::E6.mask().with(Square::E5));
+        assert_eq!(advanced, Square::E4.mask());
+    }
+
+    #[test]
+    fn iter() {
+        assert_eq!(Piece::iter().count(), 6);
+        assert_eq!(Piece::iter().next(), Some(Piece::Pawn));
+        assert_eq!(Piece::iter().next_back(), Some(Piece::Queen));
+
+        for (i, piece) in Piece::iter().enumerate() {
+            assert_eq!(piece.index(), i);
+            assert_eq!(Piece::from_index(i), Some(piece));
+        }
+    }
+
+    #[test]
+    fn classification() {
+        assert!(Piece::Knight.is_minor() && Piece::Bishop.is_minor());
+        assert!(Piece::Rook.is_major() && Piece::Queen.is_major());
+        assert!(!Piece::Pawn.is_minor() && !Piece::Pawn.is_major());
+        assert!(!Piece::King.is_minor() && !Piece::King.is_major());
+
+        for piece in Piece::iter() {
+            assert_eq!(piece.is_promotable(), piece.is_minor() || piece.is_major());
+        }
+
+        assert_eq!(Piece::PROMOTIONS.len(), 4);
+        assert!(!Piece::PROMOTIONS.contains(&Piece::King));
+    }
+
+    #[test]
+    fn unicode() {
+        assert_eq!(Piece::King.unicode(Color::White), '♔');
+        assert_eq!(Piece::Pawn.unicode(Color::Black), '♟');
+        assert_eq!(Piece::Knight.unicode(Color::White), '♘');
+        assert_eq!(Piece::Queen.unicode(Color::Black), '♛');
+    }
+
+    #[test]
+    fn value_ordering() {
+        assert!(Piece::Pawn.value() < Piece::Knight.value());
+        assert!(Piece::Knight.value() <= Piece::Bishop.value());
+        assert!(Piece::Bishop.value() < Piece::Rook.value());
+        assert!(Piece::Rook.value() < Piece::Queen.value());
+        assert_eq!(Piece::Pawn.value(), 100);
+        assert_eq!(Piece::King.value(), 0);
+    }
+
+    #[test]
+    fn custom_piece_values() {
+        let values = PieceValues::default().with(Piece::Queen, 950);
+
+        assert_eq!(Piece::Queen.value(), 900);
+        assert_eq!(Piece::Queen.value_with(&values), 950);
+        assert_eq!(Piece::Rook.value_with(&values), Piece::Rook.value());
+    }
+}
@@ -2,8 +2,10 @@ use std::cmp::Ordering;
 
 use crate::bitmask::Bitmask;
 use crate::cached;
+use crate::castle::{CastleDir, CastleRights};
 use crate::color::Color;
-use crate::piece::Piece;
+use crate::fen::FenParseError;
+use crate::piece::{Piece, PieceValues};
 use crate::square::{File, Rank, Square};
 
 /// Position stores information about the locations
@@ -75,7 +77,17 @@ impl Position {
         self.masks[7]
     }
 
-    /// Get the internal masks array.
+    /// Get the internal masks array, laid out as:
+    /// 0 => White Pieces
+    /// 1 => Black Pieces
+    /// 2 => Pawns
+    /// 3 => Kings
+    /// 4 => Rooks
+    /// 5 => Knights
+    /// 6 => Bishops
+    /// 7 => Queens
+    /// Use 'Piece::mask_index' to get the index for a piece type,
+    /// and 'Color as usize' to get the index for a color.
     pub fn masks(&self) -> &[Bitmask; 8] {
         &self.masks
     }
@@ -95,12 +107,12 @@ impl Position {
 
     /// Get a mask of all pieces of the given type/color on the specified rank.
     pub fn get_pieces_on_rank(&self, piece: Piece, color: Color, rank: Rank) -> Bitmask {
-        (self.masks[piece.index()] & self.color_mask(color)) & Bitmask::EMPTY.with_rank(rank)
+        (self.masks[piece.mask_index()] & self.color_mask(color)) & Bitmask::EMPTY.with_rank(rank)
     }
 
     /// Get a mask of all pieces of the given type/color on the specified file.
     pub fn get_pieces_on_file(&self, piece: Piece, color: Color, file: File) -> Bitmask {
-        (self.masks[piece.index()] & self.color_mask(color)) & Bitmask::EMPTY.with_file(file)
+        (self.masks[piece.mask_index()] & self.color_mask(color)) & Bitmask::EMPTY.with_file(file)
     }
 
     /// All squares occupied by a piece, of any type, of any color.
@@ -113,18 +125,40 @@ impl Position {
         self.masks[0].count() + self.masks[1].count()
     }
 
+    /// The number of pieces of the given type and color.
+    pub fn piece_count(&self, piece: Piece, color: Color) -> u8 {
+        (self.masks[piece.mask_index()] & self.color_mask(color)).count()
+    }
+
+    /// The material balance in centipawns from white's perspective,
+    /// positive when white is ahead, using 'Piece::value'.
+    pub fn material_balance(&self) -> i32 {
+        Piece::iter()
+            .map(|piece| {
+                piece.value()
+                    * (self.piece_count(piece, Color::White) as i32
+                        - self.piece_count(piece, Color::Black) as i32)
+            })
+            .sum()
+    }
+
     /// Returns a mask of all other pieces of the provided type/color that
     /// can see the square, respecting the blockers bitmask, but not pins/checks.
     pub fn pieces_that_see_square(&self, square: Square, piece: Piece, color: Color) -> Bitmask {
         let mut result = Bitmask::EMPTY;
         let blockers = self.occupied();
 
+        // pawns attack diagonally forward, so the pawns that see the square stand
+        // on the squares an enemy pawn on the square would attack.
+        let relevant = match piece {
+            Piece::Pawn => piece.relevant_squares(square, !color),
+            _ => piece.relevant_squares(square, color),
+        };
+
         // for all squares occupied by pieces that could see the square
-        for candidate in piece.relevant_squares(square, color)
-            & (self.masks[2 + piece.index()] & self.color_mask(color))
-        {
+        for candidate in relevant & (self.masks[piece.mask_index()] & self.color_mask(color)) {
             // if there are no blockers between the candidate and the square, it can see the square.
-            if Bitmask(cached::BETWEEN[square as usize][candidate as usize]).intersects(blockers) {
+            if !Bitmask(cached::BETWEEN[square as usize][candidate as usize]).intersects(blockers) {
                 result.set(candidate);
             }
         }
@@ -132,6 +166,288 @@ impl Position {
         result
     }
 
+    /// All pieces of the given color that attack the square, with a clear line to it.
+    /// Only the front piece of a battery is included, since the pieces behind it are
+    /// blocked until it moves. Like 'pieces_that_see_square', pins and checks are ignored.
+    pub fn attackers_of(&self, square: Square, color: Color) -> Bitmask {
+        self.attackers_through(square, color, self.occupied())
+    }
+
+    /// The attackers of the square, as in 'attackers_of', but only counting the
+    /// pieces in 'occupied' as present, so pieces can be lifted off the board
+    /// to reveal the sliders behind them.
+    fn attackers_through(&self, square: Square, color: Color, occupied: Bitmask) -> Bitmask {
+        Piece::iter().fold(Bitmask::EMPTY, |attackers, piece| {
+            // pawns attack diagonally forward, so look from the square as an enemy pawn.
+            let sees = match piece {
+                Piece::Pawn => piece.relevant_squares(square, !color),
+                _ => piece.moves(square, occupied, color).0,
+            };
+
+            attackers | (sees & self.masks[piece.mask_index()] & self.color_mask(color) & occupied)
+        })
+    }
+
+    /// Static exchange evaluation of the capture of the piece on 'to' by the piece
+    /// on 'from', using the standard 'PieceValues'. See 'see_with'.
+    pub fn see(&self, from: Square, to: Square) -> i32 {
+        self.see_with(from, to, &PieceValues::default())
+    }
+
+    /// Static exchange evaluation, the net material won (or lost, if negative)
+    /// by the side on 'from' if both sides keep recapturing on 'to' with their
+    /// least valuable attacker, stopping whenever continuing would lose material.
+    /// Sliders behind a capturing piece join in as the exchange reveals them.
+    /// Pins, checks, en passant, and promotions are not considered.
+    pub fn see_with(&self, from: Square, to: Square, values: &PieceValues) -> i32 {
+        let Some((color, mut attacker)) = self.piece_at(from) else {
+            return 0;
+        };
+
+        // gains[d] is the material won by the side making the d'th capture,
+        // assuming the opponent recaptures everything after it.
+        let mut gains = vec![self
+            .piece_at(to)
+            .map_or(0, |(_, victim)| values.get(victim))];
+        let mut occupied = self.occupied().without(from);
+        let mut side = !color;
+
+        while let Some((square, piece)) = self.least_valuable_attacker(to, side, occupied, values) {
+            // the king can't recapture onto a square the opponent still attacks.
+            if piece == Piece::King && !self.attackers_through(to, !side, occupied).is_empty() {
+                break;
+            }
+
+            gains.push(values.get(attacker) - gains[gains.len() - 1]);
+            occupied.remove(square);
+            attacker = piece;
+            side = !side;
+        }
+
+        // walk back up the sequence, letting each side stop capturing when it is better off.
+        while gains.len() > 1 {
+            let last = gains.pop().unwrap();
+            let prev = gains.last_mut().unwrap();
+            *prev = -(-*prev).max(last);
+        }
+
+        gains[0]
+    }
+
+    /// The least valuable piece of the color attacking the square, counting only the
+    /// pieces in 'occupied'. The king is always tried last, since it can only capture
+    /// when nothing else can.
+    fn least_valuable_attacker(
+        &self,
+        square: Square,
+        color: Color,
+        occupied: Bitmask,
+        values: &PieceValues,
+    ) -> Option<(Square, Piece)> {
+        let attackers = self.attackers_through(square, color, occupied);
+
+        let mut pieces = Piece::ALL;
+        pieces.sort_by_key(|piece| (*piece == Piece::King, values.get(*piece)));
+
+        pieces.into_iter().find_map(|piece| {
+            (attackers & self.masks[piece.mask_index()])
+                .first()
+                .map(|square| (square, piece))
+        })
+    }
+
+    /// Whether any piece of the color 'by' attacks the square. Cheaper than
+    /// 'attackers_of' since it checks the leapers first and stops at the
+    /// first attacker it finds, without building the full mask.
+    pub fn is_square_attacked(&self, square: Square, by: Color) -> bool {
+        let enemy = self.color_mask(by);
+        let pawns = Piece::Pawn.relevant_squares(square, !by);
+
+        pawns.intersects(self.pawns() & enemy)
+            || Bitmask(cached::KNIGHT[square as usize]).intersects(self.knights() & enemy)
+            || Bitmask(cached::KING[square as usize]).intersects(self.kings() & enemy)
+            || self
+                .attacks_from(square, Piece::Bishop, by)
+                .intersects(self.diagonal_sliders(by))
+            || self
+                .attacks_from(square, Piece::Rook, by)
+                .intersects(self.orthogonal_sliders(by))
+    }
+
+    /// The squares a piece of the given type/color would attack if it were placed
+    /// on the square, respecting the blockers in the position. The piece is not
+    /// actually placed, so this works for empty and occupied squares alike.
+    pub fn attacks_from(&self, square: Square, piece: Piece, color: Color) -> Bitmask {
+        // we only care about attacks, so pawn pushes are discarded.
+        piece.moves(square, self.occupied(), color).0
+    }
+
+    /// Squares occupied by pieces of the given color that are attacked by the
+    /// opponent and defended by exactly one friendly piece. These pieces are
+    /// vulnerable if their only defender is removed or overloaded.
+    pub fn singly_defended(&self, color: Color) -> Bitmask {
+        let mut attacked = Bitmask::EMPTY;
+        let mut defended = Bitmask::EMPTY;
+        let mut defended_twice = Bitmask::EMPTY;
+
+        for (piece, mask) in self.pieces() {
+            for square in mask & self.color_mask(!color) {
+                attacked |= self.attacks_from(square, piece, !color);
+            }
+
+            for square in mask & self.color_mask(color) {
+                let defends = self.attacks_from(square, piece, color);
+
+                // anything that was already defended is now defended at least twice.
+                defended_twice |= defended & defends;
+                defended |= defends;
+            }
+        }
+
+        self.color_mask(color) & attacked & defended & !defended_twice
+    }
+
+    /// Whether neither side has enough material to deliver checkmate, which is
+    /// the case for K vs K, K+minor vs K, and kings with only bishops that all
+    /// stand on the same square color. Positions with any pawns, rooks, or
+    /// queens, or with two minor pieces including a knight, are not drawn.
+    pub fn is_insufficient_material(&self) -> bool {
+        if !(self.pawns() | self.rooks() | self.queens()).is_empty() {
+            return false;
+        }
+
+        let bishops = self.bishops();
+        let minors = self.knights() | bishops;
+
+        if minors.count() <= 1 {
+            return true;
+        }
+
+        // bishops that all share a square color can never attack the other color,
+        // so neither king can ever be mated.
+        self.knights().is_empty()
+            && (bishops.into_iter().all(Square::is_light)
+                || bishops.into_iter().all(Square::is_dark))
+    }
+
+    /// The same position from the other side of the board, mirrored vertically
+    /// with the colors of every piece swapped, so white pieces on rank 1 become
+    /// black pieces on rank 8. The en passant square is mirrored along with it.
+    pub fn flip_colors(&self) -> Position {
+        let mut masks = self.masks.map(Bitmask::mirror_vertical);
+        masks.swap(0, 1);
+
+        Position {
+            masks,
+            enps: self.enps.map(Square::flip),
+            halfmoves: self.halfmoves,
+        }
+    }
+
+    /// Pawns of the color that share their file with another pawn of the same color.
+    pub fn doubled_pawns(&self, color: Color) -> Bitmask {
+        let pawns = self.pawns() & self.color_mask(color);
+
+        File::iter()
+            .map(|file| Bitmask::EMPTY.with_file(file) & pawns)
+            .filter(|on_file| on_file.count() > 1)
+            .fold(Bitmask::EMPTY, |doubled, on_file| doubled | on_file)
+    }
+
+    /// Pawns of the color without any friendly pawns on the adjacent files.
+    pub fn isolated_pawns(&self, color: Color) -> Bitmask {
+        let pawns = self.pawns() & self.color_mask(color);
+
+        pawns
+            .into_iter()
+            .filter(|square| {
+                let file = Bitmask::EMPTY.with_file(square.file());
+                !(file.shift_east() | file.shift_west()).intersects(pawns)
+            })
+            .collect()
+    }
+
+    /// Pawns of the color without any enemy pawns ahead of them
+    /// on their own file or the adjacent files.
+    pub fn passed_pawns(&self, color: Color) -> Bitmask {
+        let pawns = self.pawns() & self.color_mask(color);
+        let enemy_pawns = self.pawns() & self.color_mask(!color);
+
+        pawns
+            .into_iter()
+            .filter(|square| {
+                // walk the pawn forward to the end of the board, collecting
+                // the squares an enemy pawn could block or capture it on.
+                let mut front = Bitmask::EMPTY;
+                let mut ahead = square.mask();
+
+                for _ in 0..7 {
+                    ahead = match color {
+                        Color::White => ahead.shift_north(),
+                        Color::Black => ahead.shift_south(),
+                    };
+
+                    front |= ahead | ahead.shift_east() | ahead.shift_west();
+                }
+
+                !front.intersects(enemy_pawns)
+            })
+            .collect()
+    }
+
+    /// A Zobrist hash of the piece placement, side to move, castling rights,
+    /// and en passant file. Positions equal in all four hash identically.
+    pub fn zobrist_hash(&self, turn: Color, castle: &CastleRights) -> u64 {
+        let mut hash = 0;
+
+        for (piece, mask) in self.pieces() {
+            for color in [Color::White, Color::Black] {
+                let keys = &cached::ZOBRIST_PIECES[color as usize * 6 + piece.index()];
+
+                for square in mask & self.color_mask(color) {
+                    hash ^= keys[square as usize];
+                }
+            }
+        }
+
+        if turn == Color::Black {
+            hash ^= cached::ZOBRIST_BLACK_TO_MOVE;
+        }
+
+        hash ^= zobrist_castle(castle);
+
+        if let Some(enps) = self.enps {
+            hash ^= cached::ZOBRIST_EN_PASSANT[enps.file() as usize];
+        }
+
+        hash
+    }
+
+    /// The Zobrist keys of the piece placement and en passant file that differ
+    /// between 'self' and 'other'. XOR'ing this into the hash of one position
+    /// gives the hash of the other, visiting only the squares that changed.
+    pub fn zobrist_delta(&self, other: &Self) -> u64 {
+        let mut delta = 0;
+
+        for ((piece, fr_mask), (_, to_mask)) in self.pieces().into_iter().zip(other.pieces()) {
+            for color in [Color::White, Color::Black] {
+                let keys = &cached::ZOBRIST_PIECES[color as usize * 6 + piece.index()];
+                let changed =
+                    (fr_mask & self.color_mask(color)) ^ (to_mask & other.color_mask(color));
+
+                for square in changed {
+                    delta ^= keys[square as usize];
+                }
+            }
+        }
+
+        for enps in [self.enps, other.enps].into_iter().flatten() {
+            delta ^= cached::ZOBRIST_EN_PASSANT[enps.file() as usize];
+        }
+
+        delta
+    }
+
     /// All pieces and their type, agnostic of color.
     pub fn pieces(&self) -> [(Piece, Bitmask); 6] {
         [
@@ -231,7 +547,7 @@ impl Position {
             Color::Black => self.masks[1].set(square),
         };
 
-        self.masks[2 + piece.index()].set(square);
+        self.masks[piece.mask_index()].set(square);
 
         displaced
     }
@@ -256,8 +572,8 @@ impl Position {
                     // and then the piece mask.
                     self.masks[color as usize].remove(from);
                     self.masks[color as usize].set(dest);
-                    self.masks[2 + piece.index()].remove(from);
-                    self.masks[2 + piece.index()].set(dest);
+                    self.masks[piece.mask_index()].remove(from);
+                    self.masks[piece.mask_index()].set(dest);
                 }
             }
             // Set a square to occupied, by a given piece, for a given color.
@@ -357,8 +673,32 @@ impl Position {
             }
         }
 
+        // a move onto a square another move is leaving, for example a swap, can't be
+        // played in sequence without clobbering, so it is split into a remove and an add.
+        let sources = changes
+            .iter()
+            .filter_map(|change| match change {
+                BoardChange::Move(fr, _) => Some(*fr),
+                _ => None,
+            })
+            .collect::<Bitmask>();
+
+        let mut split = Vec::new();
+        for change in changes.iter_mut() {
+            if let BoardChange::Move(fr, to) = *change {
+                if sources.has(to) {
+                    let (color, piece) =
+                        self.piece_at(fr).expect("Unreachable 000004 was reached!");
+                    *change = BoardChange::Remove(fr);
+                    split.push(BoardChange::Add(piece, to, color));
+                }
+            }
+        }
+
+        changes.append(&mut split);
+
         // sort the changes so they occur in the right order.
-        changes.sort_unstable_by(|left, right| left.priority().cmp(&right.priority()));
+        changes.sort_by_key(BoardChange::priority);
 
         changes
     }
@@ -376,6 +716,60 @@ impl Position {
         }
     }
 
+    /// The inverse of 'to_char_grid', parsing a grid of piece ids with rank 8
+    /// in the first row and spaces for empty squares into a position, with no
+    /// en passant square and a halfmove clock of 0. Any other character is
+    /// rejected with 'FenParseError::BadPosition'.
+    pub fn from_char_grid(grid: [[char; 8]; 8]) -> Result<Position, FenParseError> {
+        let mut masks = [Bitmask::EMPTY; 8];
+
+        for (rank, row) in Rank::iter().rev().zip(grid) {
+            for (file, id) in File::iter().zip(row) {
+                if id == ' ' {
+                    continue;
+                }
+
+                let piece = Piece::from_id(id).ok_or(FenParseError::BadPosition)?;
+                let square = Square::new(file, rank);
+
+                masks[piece.mask_index()].set(square);
+                masks[Color::of_char(id) as usize].set(square);
+            }
+        }
+
+        Ok(Position::from_raw_parts(masks, 0, None))
+    }
+
+    /// Render the board as a bordered 8x8 grid of piece ids, with rank 8 on top,
+    /// rank labels on the left, file labels along the bottom, and dots for empty
+    /// squares. Useful for logging positions in tests.
+    pub fn to_ascii(&self) -> String {
+        let border = "  +-----------------+";
+        let mut result = String::from(border);
+
+        for (rank, row) in Rank::iter().rev().zip(self.to_char_grid()) {
+            result.push_str(&format!("\n{} |", rank.to_char()));
+
+            for id in row {
+                result.push(' ');
+                result.push(if id == ' ' { '.' } else { id });
+            }
+
+            result.push_str(" |");
+        }
+
+        result.push('\n');
+        result.push_str(border);
+        result.push_str("\n   ");
+
+        for file in File::iter() {
+            result.push(' ');
+            result.push(file.to_char_lower());
+        }
+
+        result
+    }
+
     /// Convert to a grid of chracters, denoted using
     /// their algebraic names.
     pub fn to_char_grid(&self) -> [[char; 8]; 8] {
@@ -431,6 +825,24 @@ impl Position {
     }
 }
 
+/// The Zobrist keys of the castle rights that haven't been lost.
+pub(crate) fn zobrist_castle(castle: &CastleRights) -> u64 {
+    // keys are in the order white kingside, white queenside, black kingside, black queenside.
+    let rights = [Color::White, Color::Black]
+        .into_iter()
+        .flat_map(|color| [(color, CastleDir::Short), (color, CastleDir::Long)]);
+
+    let mut hash = 0;
+
+    for (key, (color, dir)) in cached::ZOBRIST_CASTLE.iter().zip(rights) {
+        if !castle.has_lost(color, dir) {
+            hash ^= key;
+        }
+    }
+
+    hash
+}
+
 /// A representation of a change on the board.
 #[derive(Copy, Clone, Debug, Hash, PartialEq)]
 pub enum BoardChange {
@@ -445,9 +857,9 @@ pub enum BoardChange {
 impl BoardChange {
     pub fn priority(&self) -> u8 {
         match self {
-            Self::Remove(_) => 2,
+            Self::Remove(_) => 0,
             Self::Move(_, _) => 1,
-            Self::Add(_, _, _) => 0,
+            Self::Add(_, _, _) => 2,
         }
     }
 }
@@ -494,6 +906,7 @@ impl Default for Position {
 
 #[cfg(test)]
 mod tests {
+    use crate::state::BoardState;
     use crate::FenParser;
 
     use super::*;
@@ -514,6 +927,39 @@ mod tests {
         assert_eq!(expected, Position::default().to_char_grid());
     }
 
+    #[test]
+    fn to_ascii() {
+        let expected = [
+            "  +-----------------+",
+            "8 | r n b q k b n r |",
+            "7 | p p p p p p p p |",
+            "6 | . . . . . . . . |",
+            "5 | . . . . . . . . |",
+            "4 | . . . . . . . . |",
+            "3 | . . . . . . . . |",
+            "2 | P P P P P P P P |",
+            "1 | R N B Q K B N R |",
+            "  +-----------------+",
+            "    a b c d e f g h",
+        ]
+        .join("\n");
+
+        assert_eq!(Position::default().to_ascii(), expected);
+    }
+
+    #[test]
+    fn from_char_grid() {
+        let position = Position::default();
+        assert_eq!(
+            Position::from_char_grid(position.to_char_grid()).unwrap(),
+            position
+        );
+
+        let mut grid = position.to_char_grid();
+        grid[4][4] = 'x';
+        assert!(Position::from_char_grid(grid).is_err());
+    }
+
     #[test]
     fn board_as_fen_string() {
         let expected = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR";
@@ -541,4 +987,350 @@ mod tests {
 
         assert_eq!(from.to_char_grid(), dest.to_char_grid())
     }
+
+    #[test]
+    fn attacks_from_hypothetical_rook() {
+        let expected = Bitmask::EMPTY
+            .with_rank(Rank::_4)
+            .without(Square::D4)
+            .with(Square::D2)
+            .with(Square::D3)
+            .with(Square::D5)
+            .with(Square::D6)
+            .with(Square::D7);
+
+        assert_eq!(
+            Position::default().attacks_from(Square::D4, Piece::Rook, Color::White),
+            expected
+        );
+    }
+
+    #[test]
+    fn singly_defended() {
+        // the knight on e3 is defended only by the king, while the knight
+        // on c3 is defended by the pawn on b2 and the rook on c1.
+        let position = FenParser::parse("4r2k/8/8/b7/8/2N1N3/1P2K3/2R5 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        assert_eq!(position.singly_defended(Color::White), Square::E3.mask());
+    }
+
+    #[test]
+    fn zobrist_hash() {
+        let state = BoardState::default();
+        let position = state.position();
+        let hash = position.zobrist_hash(state.turn(), &state.castle());
+
+        // the keys are constant, so the hash of the default position never changes.
+        assert_eq!(hash, 0x3e7951a3396dfd6e);
+        assert_eq!(
+            hash,
+            Position::default().zobrist_hash(Color::White, &CastleRights::default())
+        );
+        assert_ne!(hash, position.zobrist_hash(Color::Black, &state.castle()));
+        assert_ne!(
+            hash,
+            position.zobrist_hash(Color::White, &CastleRights::none())
+        );
+
+        let next = state.play_unchecked(Square::G1, Square::F3, None);
+        assert_ne!(
+            next.position().zobrist_hash(next.turn(), &next.castle()),
+            hash
+        );
+    }
+
+    #[test]
+    fn is_insufficient_material() {
+        let drawn = [
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/4KN2 w - - 0 1",
+            "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        ];
+        let not_drawn = [
+            "4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1",
+            "4k1b1/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+        ];
+
+        for fen in drawn {
+            let position = FenParser::parse(fen).unwrap().position().unwrap();
+            assert!(position.is_insufficient_material(), "{fen}");
+        }
+
+        for fen in not_drawn {
+            let position = FenParser::parse(fen).unwrap().position().unwrap();
+            assert!(!position.is_insufficient_material(), "{fen}");
+        }
+    }
+
+    #[test]
+    fn flip_colors() {
+        assert_eq!(Position::default().flip_colors(), Position::default());
+
+        let position = BoardState::default()
+            .play_unchecked(Square::E2, Square::E4, None)
+            .position();
+        let flipped = position.flip_colors();
+
+        assert_eq!(
+            flipped.piece_at(Square::E5),
+            Some((Color::Black, Piece::Pawn))
+        );
+        assert_eq!(flipped.piece_at(Square::E7), None);
+        assert_eq!(
+            flipped.piece_at(Square::E2),
+            Some((Color::White, Piece::Pawn))
+        );
+        assert_eq!(
+            flipped.en_passant(),
+            position.en_passant().map(Square::flip)
+        );
+        assert_eq!(flipped.flip_colors(), position);
+    }
+
+    fn assert_changes_reproduce(from: &str, dest: &str) {
+        let mut from = FenParser::parse(from).unwrap().position().unwrap();
+        let dest = FenParser::parse(dest).unwrap().position().unwrap();
+
+        for change in from.changes(&dest) {
+            from.change(change);
+        }
+
+        assert_eq!(from.to_char_grid(), dest.to_char_grid());
+    }
+
+    #[test]
+    fn changes_capture() {
+        assert_changes_reproduce(
+            "4k3/8/8/3p4/8/2N5/8/4K3 w - - 0 1",
+            "4k3/8/8/3N4/8/8/8/4K3 b - - 0 1",
+        );
+    }
+
+    #[test]
+    fn changes_promotion() {
+        assert_changes_reproduce(
+            "7k/4P3/8/8/8/8/8/4K3 w - - 0 1",
+            "4Q2k/8/8/8/8/8/8/4K3 b - - 0 1",
+        );
+    }
+
+    #[test]
+    fn changes_capture_promotion() {
+        let from = FenParser::parse("4r2k/3P4/8/8/8/8/8/4K3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+        let dest = FenParser::parse("4Q2k/8/8/8/8/8/8/4K3 b - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        // the pawn and rook are removed and the queen is added, nothing is moved.
+        let changes = from.changes(&dest);
+        assert_eq!(changes.len(), 3);
+        assert!(!changes
+            .iter()
+            .any(|change| matches!(change, BoardChange::Move(_, _))));
+
+        assert_changes_reproduce(
+            "4r2k/3P4/8/8/8/8/8/4K3 w - - 0 1",
+            "4Q2k/8/8/8/8/8/8/4K3 b - - 0 1",
+        );
+    }
+
+    #[test]
+    fn changes_swap() {
+        // a chess960 castle where the king and rook trade squares.
+        assert_changes_reproduce(
+            "4k3/8/8/8/8/8/8/5KR1 w G - 0 1",
+            "4k3/8/8/8/8/8/8/5RK1 b - - 0 1",
+        );
+    }
+
+    #[test]
+    fn piece_count_and_material_balance() {
+        let position = Position::default();
+
+        assert_eq!(position.piece_count(Piece::Pawn, Color::White), 8);
+        assert_eq!(position.piece_count(Piece::Knight, Color::Black), 2);
+        assert_eq!(position.piece_count(Piece::Queen, Color::White), 1);
+        assert_eq!(position.material_balance(), 0);
+
+        // black is missing the queen, white is missing a knight.
+        let position = FenParser::parse("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/R1BQKBNR w KQkq - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        assert_eq!(position.material_balance(), 900 - 320);
+    }
+
+    #[test]
+    fn pieces_that_see_square_blocked() {
+        let position = FenParser::parse("4k3/8/8/8/8/8/1P6/B3K3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        // the bishop on a1 is blocked by the pawn on b2.
+        assert_eq!(
+            position.pieces_that_see_square(Square::E5, Piece::Bishop, Color::White),
+            Bitmask::EMPTY
+        );
+        assert_eq!(
+            position.pieces_that_see_square(Square::B2, Piece::Bishop, Color::White),
+            Square::A1.mask()
+        );
+    }
+
+    #[test]
+    fn attackers_of_battery() {
+        let position = FenParser::parse("3rk3/8/8/3p4/2P5/4N3/3R4/3RK3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        // the rook on d1 is behind the rook on d2, so only the front rook attacks.
+        let expected = Square::C4.mask().with(Square::E3).with(Square::D2);
+        assert_eq!(position.attackers_of(Square::D5, Color::White), expected);
+        assert_eq!(
+            position.attackers_of(Square::D5, Color::Black),
+            Square::D8.mask()
+        );
+    }
+
+    #[test]
+    fn attackers_of_blocked_bishop() {
+        let position = FenParser::parse("4k3/8/8/8/8/2n5/8/B3K3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        // the bishop on a1 attacks the knight on c3 but not the squares behind it.
+        assert_eq!(
+            position.attackers_of(Square::C3, Color::White),
+            Square::A1.mask()
+        );
+        assert_eq!(
+            position.attackers_of(Square::E5, Color::White),
+            Bitmask::EMPTY
+        );
+        assert_eq!(
+            position.attackers_of(Square::D2, Color::White),
+            Square::E1.mask()
+        );
+    }
+
+    #[test]
+    fn is_square_attacked() {
+        let position = FenParser::parse("7k/8/8/8/8/8/8/K7 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        assert!(!position.is_square_attacked(Square::E4, Color::White));
+        assert!(!position.is_square_attacked(Square::E4, Color::Black));
+        assert!(position.is_square_attacked(Square::B2, Color::White));
+
+        let position = FenParser::parse("7k/8/8/8/q6P/8/8/K7 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        assert!(position.is_square_attacked(Square::E4, Color::Black));
+        assert!(position.is_square_attacked(Square::H4, Color::Black));
+        assert!(!position.is_square_attacked(Square::E4, Color::White));
+        assert!(position.is_square_attacked(Square::G5, Color::White));
+    }
+
+    #[test]
+    fn see_pawn_takes_pawn() {
+        let undefended = FenParser::parse("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+        let defended = FenParser::parse("4k3/8/4p3/3p4/4P3/8/8/4K3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        assert_eq!(undefended.see(Square::E4, Square::D5), 100);
+        assert_eq!(defended.see(Square::E4, Square::D5), 0);
+    }
+
+    #[test]
+    fn see_queen_takes_defended_pawn() {
+        let position = FenParser::parse("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        assert_eq!(position.see(Square::D1, Square::D5), 100 - 900);
+    }
+
+    #[test]
+    fn see_x_ray() {
+        // the rook on d1 backs up the rook on d2, so the pawn is won.
+        let position = FenParser::parse("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        assert_eq!(position.see(Square::D2, Square::D5), 100);
+
+        // without the second rook, taking the pawn loses the rook.
+        let position = FenParser::parse("3rk3/8/8/3p4/8/8/3R4/4K3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        assert_eq!(position.see(Square::D2, Square::D5), 100 - 500);
+    }
+
+    #[test]
+    fn see_custom_piece_values() {
+        // the rook takes a queen defended by a bishop.
+        let position = FenParser::parse("4k3/8/2b5/3q4/8/8/8/3RK3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        let high = PieceValues::default().with(Piece::Queen, 1200);
+        let low = PieceValues::default().with(Piece::Queen, 450);
+
+        assert_eq!(position.see(Square::D1, Square::D5), 900 - 500);
+        assert_eq!(position.see_with(Square::D1, Square::D5, &high), 1200 - 500);
+        assert_eq!(position.see_with(Square::D1, Square::D5, &low), 450 - 500);
+    }
+
+    #[test]
+    fn pawn_structure() {
+        // white has doubled pawns on c2 and c3 and an isolated, passed pawn on a5.
+        // black's pawn on e7 stops the pawns on d4 and f2 from being passed.
+        let position = FenParser::parse("4k3/4p3/8/P7/3P4/2P5/2P2P2/4K3 w - - 0 1")
+            .unwrap()
+            .position()
+            .unwrap();
+
+        assert_eq!(
+            position.doubled_pawns(Color::White),
+            Square::C2.mask().with(Square::C3)
+        );
+        assert_eq!(
+            position.isolated_pawns(Color::White),
+            Square::A5.mask().with(Square::F2)
+        );
+        assert_eq!(
+            position.passed_pawns(Color::White),
+            Square::A5.mask().with(Square::C2).with(Square::C3)
+        );
+        assert_eq!(position.isolated_pawns(Color::Black), Square::E7.mask());
+        assert_eq!(position.passed_pawns(Color::Black), Bitmask::EMPTY);
+    }
 }
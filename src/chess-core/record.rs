@@ -1,48 +1,303 @@
+use crate::color::Color;
 use crate::square::Square;
 use arrayvec::ArrayString;
 
-/// shorthand for ArrayString<7>.
-pub type MoveString = ArrayString<7>;
+/// shorthand for ArrayString<8>. The longest SAN, like 'exd8=Q' or 'Qa1xb2',
+/// is 6 chars, leaving room for a '+' or '#' suffix and a spare byte.
+pub type MoveString = ArrayString<8>;
+
+/// A move in a MoveRecord, with the annotations attached to it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RecordedMove {
+    from: Square,
+    dest: Square,
+    /// The move in Standard Algebraic Notation.
+    notation: MoveString,
+    /// A comment on the move, written after it in '{}'.
+    comment: Option<String>,
+    /// Numeric Annotation Glyphs, like 1 for '!' or 4 for '??'.
+    nags: Vec<u8>,
+}
+
+impl RecordedMove {
+    pub fn new(from: Square, dest: Square, notation: MoveString) -> Self {
+        Self {
+            from,
+            dest,
+            notation,
+            comment: None,
+            nags: Vec::new(),
+        }
+    }
+
+    pub fn from(&self) -> Square {
+        self.from
+    }
+
+    pub fn dest(&self) -> Square {
+        self.dest
+    }
+
+    pub fn notation(&self) -> &MoveString {
+        &self.notation
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    pub fn nags(&self) -> &[u8] {
+        &self.nags
+    }
+
+    /// Set or clear the comment on the move.
+    pub fn set_comment(&mut self, comment: Option<String>) {
+        self.comment = comment;
+    }
+
+    /// Replace the NAGs of the move.
+    pub fn set_nags(&mut self, nags: Vec<u8>) {
+        self.nags = nags;
+    }
+
+    /// Add a NAG to the move.
+    pub fn add_nag(&mut self, nag: u8) {
+        self.nags.push(nag);
+    }
+}
 
 /// A struct for recording moves.
 #[derive(Clone, Debug, Hash)]
 pub struct MoveRecord {
-    moves: Vec<(Square, Square, MoveString)>,
+    moves: Vec<RecordedMove>,
+    /// The fullmove number of the first move.
+    fullmoves: u16,
+    /// The side that plays the first move.
+    turn: Color,
 }
 
 impl MoveRecord {
     pub fn new() -> Self {
-        Self { moves: Vec::new() }
+        Self::starting_at(1, Color::White)
+    }
+
+    /// Create a record whose first move is played by 'turn' on fullmove 'fullmoves'.
+    pub fn starting_at(fullmoves: u16, turn: Color) -> Self {
+        Self {
+            moves: Vec::new(),
+            fullmoves,
+            turn,
+        }
     }
 
     /// Write a move to the internal buffer.
     pub fn write(&mut self, from: Square, dest: Square, notation: MoveString) {
-        self.moves.push((from, dest, notation))
+        self.moves.push(RecordedMove::new(from, dest, notation))
     }
 
-    /// Get the last move written to the record, in the format (from, dest, notation).
-    pub fn last(&self) -> Option<&(Square, Square, MoveString)> {
+    /// Write a move to the internal buffer, keeping its annotations.
+    pub fn push(&mut self, mv: RecordedMove) {
+        self.moves.push(mv)
+    }
+
+    /// Get the last move written to the record.
+    pub fn last(&self) -> Option<&RecordedMove> {
         self.moves.last()
     }
 
+    /// Get the last move written to the record, to annotate it.
+    pub fn last_mut(&mut self) -> Option<&mut RecordedMove> {
+        self.moves.last_mut()
+    }
+
     /// Get the move that occured at the move index.
-    pub fn index(&self, index: usize) -> Option<&(Square, Square, MoveString)> {
-        if index >= self.moves.len() {
-            None
-        } else {
-            Some(&self.moves[index])
-        }
+    pub fn index(&self, index: usize) -> Option<&RecordedMove> {
+        self.moves.get(index)
+    }
+
+    /// Get the move that occured at the move index, to annotate it.
+    pub fn index_mut(&mut self, index: usize) -> Option<&mut RecordedMove> {
+        self.moves.get_mut(index)
     }
 
     /// Fork the record, returning everything before the index, inclusive.
     pub fn fork_at(&self, index: usize) -> Self {
         Self {
             moves: self.moves[..=index].to_vec(),
+            ..*self
         }
     }
 
+    /// The number of moves in the record.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Whether no moves have been written to the record.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Iterate the moves in the order they were played.
+    pub fn iter(&self) -> std::slice::Iter<'_, RecordedMove> {
+        self.moves.iter()
+    }
+
     /// Pop off a move.
-    pub fn pop(&mut self) -> Option<(Square, Square, MoveString)> {
+    pub fn pop(&mut self) -> Option<RecordedMove> {
         self.moves.pop()
     }
+
+    /// The record as numbered movetext, like '1. e4 e5 2. Nf3 Nc6'. If black
+    /// plays the first move, its number is written as '1... c5'. NAGs follow
+    /// their move as '$1', then the comment in braces, which can't contain '}'.
+    pub fn to_movetext(&self) -> String {
+        let mut movetext = String::new();
+        let mut fullmoves = self.fullmoves;
+        let mut turn = self.turn;
+
+        for (index, mv) in self.moves.iter().enumerate() {
+            if index != 0 {
+                movetext.push(' ');
+            }
+
+            // a black move is numbered again after a comment interrupts the pair.
+            let interrupted = index == 0 || self.moves[index - 1].comment.is_some();
+
+            match turn {
+                Color::White => movetext.push_str(&format!("{fullmoves}. ")),
+                Color::Black if interrupted => movetext.push_str(&format!("{fullmoves}... ")),
+                Color::Black => {}
+            }
+
+            movetext.push_str(&mv.notation);
+
+            for nag in &mv.nags {
+                movetext.push_str(&format!(" ${nag}"));
+            }
+
+            if let Some(comment) = &mv.comment {
+                movetext.push_str(&format!(" {{{}}}", comment.replace('}', "")));
+            }
+
+            if turn == Color::Black {
+                fullmoves += 1;
+            }
+
+            turn = !turn;
+        }
+
+        movetext
+    }
+}
+
+impl IntoIterator for MoveRecord {
+    type IntoIter = std::vec::IntoIter<RecordedMove>;
+    type Item = RecordedMove;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.moves.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveRecord {
+    type IntoIter = std::slice::Iter<'a, RecordedMove>;
+    type Item = &'a RecordedMove;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.moves.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_all(record: &mut MoveRecord, moves: &[&str]) {
+        for san in moves {
+            record.write(Square::A1, Square::A1, MoveString::from(san).unwrap());
+        }
+    }
+
+    #[test]
+    fn iter() {
+        let mut record = MoveRecord::new();
+        assert!(record.is_empty());
+
+        record.write(Square::E2, Square::E4, MoveString::from("e4").unwrap());
+        record.write(Square::E7, Square::E5, MoveString::from("e5").unwrap());
+        record.write(Square::G1, Square::F3, MoveString::from("Nf3").unwrap());
+        assert_eq!(record.len(), 3);
+        assert!(!record.is_empty());
+
+        let sans: Vec<&str> = record.iter().map(|mv| mv.notation().as_str()).collect();
+        assert_eq!(sans, ["e4", "e5", "Nf3"]);
+
+        let mut dests = Vec::new();
+        for mv in &record {
+            dests.push(mv.dest());
+        }
+        assert_eq!(dests, [Square::E4, Square::E5, Square::F3]);
+
+        let moves: Vec<_> = record.into_iter().collect();
+        assert_eq!(
+            moves[2],
+            RecordedMove::new(Square::G1, Square::F3, MoveString::from("Nf3").unwrap())
+        );
+    }
+
+    #[test]
+    fn movetext_white_first() {
+        let mut record = MoveRecord::new();
+        assert_eq!(record.to_movetext(), "");
+
+        write_all(&mut record, &["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+        assert_eq!(record.to_movetext(), "1. e4 e5 2. Nf3 Nc6 3. Bb5");
+    }
+
+    #[test]
+    fn movetext_black_first() {
+        let mut record = MoveRecord::starting_at(1, Color::Black);
+        write_all(&mut record, &["c5", "Nf3", "d6"]);
+        assert_eq!(record.to_movetext(), "1... c5 2. Nf3 d6");
+
+        let mut record = MoveRecord::starting_at(12, Color::Black);
+        write_all(&mut record, &["Qh4#"]);
+        assert_eq!(record.to_movetext(), "12... Qh4#");
+
+        // forking keeps the numbering.
+        assert_eq!(record.fork_at(0).to_movetext(), "12... Qh4#");
+    }
+
+    #[test]
+    fn movetext_annotations() {
+        let mut record = MoveRecord::new();
+        write_all(&mut record, &["e4", "e5", "f4"]);
+
+        let mv = record.index_mut(0).unwrap();
+        mv.set_comment(Some("Best by test".to_string()));
+        mv.add_nag(1);
+        record.last_mut().unwrap().set_nags(vec![3, 18]);
+
+        assert_eq!(record.index(0).unwrap().comment(), Some("Best by test"));
+        assert_eq!(record.index(0).unwrap().nags(), [1]);
+        assert_eq!(
+            record.to_movetext(),
+            "1. e4 $1 {Best by test} 1... e5 2. f4 $3 $18"
+        );
+
+        // a brace would end the comment early.
+        record
+            .index_mut(1)
+            .unwrap()
+            .set_comment(Some("{}}".to_string()));
+        assert!(record.to_movetext().contains("1... e5 {{} 2. f4"));
+    }
+
+    #[test]
+    fn move_string_fits_annotated_san() {
+        for san in ["exd8=Q#", "Qa1xb2+", "O-O-O#"] {
+            assert_eq!(MoveString::from(san).unwrap().as_str(), san);
+        }
+    }
 }
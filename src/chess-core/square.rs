@@ -8,20 +8,20 @@ mod definitions {
     /// A single column in the board grid.
     /// A = 0, G = 7.
     #[repr(u8)]
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
     pub enum File {
         A=0, B, C, D, E, F, G, H
     }
 
     /// A single row in the board grid.
     /// _1 = 0, _8 = 7.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
     pub enum Rank {
         _1, _2, _3, _4, _5, _6, _7, _8
     }
 
     /// A single square in the board grid.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
     pub enum Square {
         A1, B1, C1, D1, E1, F1, G1, H1,
         A2, B2, C2, D2, E2, F2, G2, H2,
@@ -35,6 +35,17 @@ mod definitions {
 }
 
 impl File {
+    /// Every File, from File::A to File::H.
+    pub const ALL: [Self; 8] = {
+        let mut all = [Self::A; 8];
+        let mut i = 0;
+        while i < 8 {
+            all[i] = Self::try_idx(i as u8).unwrap();
+            i += 1;
+        }
+        all
+    };
+
     /// Create a new file, representing a column of cells vertically.
     /// Panics of the number is greater than 7, or less than 0.
     pub fn new(idx: u8) -> Self {
@@ -42,9 +53,9 @@ impl File {
     }
 
     /// Attempt to convert a number to a column of cells vertically.
-    pub fn try_idx(idx: u8) -> Option<Self> {
+    pub const fn try_idx(idx: u8) -> Option<Self> {
         // Rust doesn't give us a way to convert u8 to enum for some reason, so transmute.
-        if idx > 8 {
+        if idx > 7 {
             None
         } else {
             Some(unsafe { transmute(idx) })
@@ -103,6 +114,17 @@ impl File {
 }
 
 impl Rank {
+    /// Every Rank, from Rank::_1 to Rank::_8.
+    pub const ALL: [Self; 8] = {
+        let mut all = [Self::_1; 8];
+        let mut i = 0;
+        while i < 8 {
+            all[i] = Self::try_idx(i as u8).unwrap();
+            i += 1;
+        }
+        all
+    };
+
     /// Create a new rank, representing a row of cells horizontally.
     /// Panics if the number is greater than 7, or less than 0.
     pub fn new(idx: u8) -> Self {
@@ -110,9 +132,9 @@ impl Rank {
     }
 
     /// Attempt to convert a number to a row of cells horizontally.
-    pub fn try_idx(idx: u8) -> Option<Self> {
+    pub const fn try_idx(idx: u8) -> Option<Self> {
         // Rust doesn't give us a way to convert u8 to enum for some reason, so transmute.
-        if idx > 8 {
+        if idx > 7 {
             None
         } else {
             Some(unsafe { transmute(idx) })
@@ -156,6 +178,17 @@ impl Rank {
 }
 
 impl Square {
+    /// Every Square, in index order from Square::A1 to Square::H8.
+    pub const ALL: [Self; 64] = {
+        let mut all = [Self::A1; 64];
+        let mut i = 0;
+        while i < 64 {
+            all[i] = Self::try_idx(i as u8).unwrap();
+            i += 1;
+        }
+        all
+    };
+
     /// Try to create a new Square, representing a single cell on the board grid.
     pub fn try_new(file: u8, rank: u8) -> Option<Self> {
         Some(Self::new(File::try_idx(file)?, Rank::try_idx(rank)?))
@@ -276,6 +309,62 @@ impl Square {
         );
         (x1 - y1) == (x2 - y2) || (x1 - y2) == (x2 - y1)
     }
+
+    /// The unit (file, rank) step from self towards other, if the two squares
+    /// share an orthogonal or diagonal line. Returns None if they don't share a
+    /// line, for example a knight-shaped offset, or if the squares are the same.
+    pub fn direction_to(self, other: Self) -> Option<(i8, i8)> {
+        if self == other || !(self.shares_orthogonal(other) || self.shares_diagonal(other)) {
+            return None;
+        }
+
+        Some((
+            (other.file() as i8 - self.file() as i8).signum(),
+            (other.rank() as i8 - self.rank() as i8).signum(),
+        ))
+    }
+
+    /// Mirror the square vertically, swapping ranks 1 and 8, 2 and 7, etc,
+    /// so A1 becomes A8. This gives the same square from black's perspective.
+    pub fn flip(self) -> Self {
+        // the rank is stored in bits 3-5, so flipping them mirrors the rank.
+        Self::try_idx(self as u8 ^ 56).unwrap()
+    }
+
+    /// Whether this is a light square. Squares where the file and rank
+    /// indices sum to an odd number are light, so A1 is dark and H1 is light,
+    /// matching the standard board where each player has a light square
+    /// in the right-hand corner.
+    pub fn is_light(self) -> bool {
+        (self.file() as u8 + self.rank() as u8) % 2 == 1
+    }
+
+    /// Whether this is a dark square, the opposite of 'is_light'.
+    pub fn is_dark(self) -> bool {
+        !self.is_light()
+    }
+
+    /// The number of files between self and other.
+    pub fn file_distance(self, other: Self) -> u8 {
+        (self.file() as u8).abs_diff(other.file() as u8)
+    }
+
+    /// The number of ranks between self and other.
+    pub fn rank_distance(self, other: Self) -> u8 {
+        (self.rank() as u8).abs_diff(other.rank() as u8)
+    }
+
+    /// The number of king moves between self and other,
+    /// which is the larger of the file and rank distance.
+    pub fn chebyshev_distance(self, other: Self) -> u8 {
+        self.file_distance(other).max(self.rank_distance(other))
+    }
+
+    /// The number of orthogonal steps between self and other,
+    /// which is the sum of the file and rank distance.
+    pub fn manhattan_distance(self, other: Self) -> u8 {
+        self.file_distance(other) + self.rank_distance(other)
+    }
 }
 
 impl std::fmt::Display for Square {
@@ -284,6 +373,29 @@ impl std::fmt::Display for Square {
     }
 }
 
+impl std::str::FromStr for Square {
+    type Err = SquareParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from_string(s).ok_or(SquareParseError)
+    }
+}
+
+/// The error returned when a string can't be parsed into a Square.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SquareParseError;
+
+impl std::fmt::Display for SquareParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "square must be a file from a to h followed by a rank from 1 to 8"
+        )
+    }
+}
+
+impl std::error::Error for SquareParseError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -366,12 +478,16 @@ mod tests {
 
     #[test]
     fn rank_try_idx_out_of_bounds() {
+        assert_eq!(Rank::try_idx(7), Some(Rank::_8));
         assert_eq!(Rank::try_idx(8), None);
+        assert_eq!(Rank::try_idx(u8::MAX), None);
     }
 
     #[test]
     fn file_try_idx_out_of_bounds() {
+        assert_eq!(File::try_idx(7), Some(File::H));
         assert_eq!(File::try_idx(8), None);
+        assert_eq!(File::try_idx(u8::MAX), None);
     }
 
     #[test]
@@ -462,4 +578,99 @@ mod tests {
         assert_eq!(Square::B8.diag_edge((-1, 1)), Square::B8);
         assert_eq!(Square::B8.diag_edge((-1, -1)), Square::A7);
     }
+
+    #[test]
+    fn square_distance() {
+        assert_eq!(Square::A1.chebyshev_distance(Square::H8), 7);
+        assert_eq!(Square::A1.manhattan_distance(Square::H8), 14);
+        assert_eq!(Square::E4.chebyshev_distance(Square::D5), 1);
+        assert_eq!(Square::E4.chebyshev_distance(Square::E5), 1);
+        assert_eq!(Square::E4.manhattan_distance(Square::D5), 2);
+        assert_eq!(Square::B7.file_distance(Square::G2), 5);
+        assert_eq!(Square::B7.rank_distance(Square::G2), 5);
+        assert_eq!(Square::C3.chebyshev_distance(Square::C3), 0);
+    }
+
+    #[test]
+    fn square_is_light() {
+        assert!(Square::A1.is_dark());
+        assert!(Square::H1.is_light());
+        assert!(Square::A8.is_light());
+        assert!(Square::H8.is_dark());
+        assert!(Square::E4.is_light());
+        assert!(Square::D4.is_dark());
+        assert_eq!(Square::iter().filter(|sq| sq.is_light()).count(), 32);
+    }
+
+    #[test]
+    fn square_from_str() {
+        assert_eq!("e4".parse::<Square>(), Ok(Square::E4));
+        assert_eq!("a1".parse::<Square>(), Ok(Square::A1));
+        assert_eq!("h8".parse::<Square>(), Ok(Square::H8));
+        assert_eq!("e45".parse::<Square>(), Err(SquareParseError));
+        assert_eq!("z4".parse::<Square>(), Err(SquareParseError));
+        assert_eq!("".parse::<Square>(), Err(SquareParseError));
+
+        let error: Box<dyn std::error::Error> = Box::new(SquareParseError);
+        assert_eq!(
+            error.to_string(),
+            "square must be a file from a to h followed by a rank from 1 to 8"
+        );
+    }
+
+    #[test]
+    fn square_flip() {
+        assert_eq!(Square::A1.flip(), Square::A8);
+        assert_eq!(Square::E4.flip(), Square::E5);
+        assert_eq!(Square::H7.flip(), Square::H2);
+
+        for square in Square::iter() {
+            assert_eq!(square.flip().flip(), square);
+            assert_eq!(square.flip().file(), square.file());
+        }
+    }
+
+    #[test]
+    fn square_direction_to() {
+        // orthogonal
+        assert_eq!(Square::E4.direction_to(Square::E8), Some((0, 1)));
+        assert_eq!(Square::E4.direction_to(Square::E1), Some((0, -1)));
+        assert_eq!(Square::E4.direction_to(Square::H4), Some((1, 0)));
+        assert_eq!(Square::E4.direction_to(Square::A4), Some((-1, 0)));
+        // diagonal
+        assert_eq!(Square::E4.direction_to(Square::H7), Some((1, 1)));
+        assert_eq!(Square::E4.direction_to(Square::B1), Some((-1, -1)));
+        assert_eq!(Square::E4.direction_to(Square::A8), Some((-1, 1)));
+        assert_eq!(Square::E4.direction_to(Square::H1), Some((1, -1)));
+        // no shared line
+        assert_eq!(Square::A1.direction_to(Square::B3), None);
+        assert_eq!(Square::E4.direction_to(Square::E4), None);
+    }
+
+    #[test]
+    fn square_ord() {
+        // interleave the squares from both ends of the board.
+        let mut squares: Vec<Square> = Square::iter()
+            .take(32)
+            .zip(Square::iter().rev())
+            .flat_map(|(a, b)| [b, a])
+            .collect();
+
+        squares.sort();
+
+        assert!(squares.iter().copied().eq(Square::iter()));
+        assert!(Square::A1 < Square::B1);
+        assert!(Square::H1 < Square::A2);
+        assert!(File::A < File::H);
+        assert!(Rank::_1 < Rank::_8);
+    }
+
+    #[test]
+    fn square_all() {
+        assert_eq!(Square::ALL[0], Square::A1);
+        assert_eq!(Square::ALL[63], Square::H8);
+        assert!(Square::ALL.iter().copied().eq(Square::iter()));
+        assert!(File::ALL.iter().copied().eq(File::iter()));
+        assert!(Rank::ALL.iter().copied().eq(Rank::iter()));
+    }
 }
@@ -1,13 +1,16 @@
+use crate::bitmask::Bitmask;
+use crate::cached;
 use crate::castle::CastleDir;
 use crate::castle::CastleRights;
 use crate::color::Color;
 use crate::fen::FenParseError;
 use crate::fen::FenParser;
+use crate::generator::Move;
 use crate::generator::MoveGenerator;
 use crate::piece::Piece;
-use crate::position::Position;
+use crate::position::{zobrist_castle, Position};
 use crate::record::MoveString;
-use crate::square::Square;
+use crate::square::{File, Rank, Square};
 
 /// All of the information in a FEN, in a struct.
 #[derive(Copy, Clone, PartialEq, Hash, Debug)]
@@ -16,6 +19,8 @@ pub struct BoardState {
     castle: CastleRights,
     fullmoves: u16,
     turn: Color,
+    /// The Zobrist hash, kept up to date by 'play_unchecked'.
+    hash: u64,
 }
 
 impl BoardState {
@@ -25,9 +30,16 @@ impl BoardState {
             position,
             fullmoves,
             turn,
+            hash: position.zobrist_hash(turn, &castle),
         }
     }
 
+    /// The Zobrist hash of the state. Unlike 'Position::zobrist_hash', this is
+    /// updated with only the keys that change when a move is played.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.hash
+    }
+
     /// Get the piece locations in the state.
     pub fn position(&self) -> Position {
         self.position
@@ -68,6 +80,128 @@ impl BoardState {
         MoveGenerator::from_state(self)
     }
 
+    /// Whether the side to move is in check and has no legal moves.
+    pub fn is_checkmate(&self) -> bool {
+        let generator = self.generator();
+        generator.is_check() && !generator.has_any_moves()
+    }
+
+    /// Whether the side to move is not in check, but has no legal moves.
+    pub fn is_stalemate(&self) -> bool {
+        let generator = self.generator();
+        !generator.is_check() && !generator.has_any_moves()
+    }
+
+    /// Whether the game is drawn in this position by stalemate, the 50-move
+    /// rule, or insufficient material. Checkmate on the 100th halfmove takes
+    /// precedence over the 50-move rule. Repetition needs the game history,
+    /// see 'ChessGame::is_draw_by_repetition'.
+    pub fn is_draw(&self) -> bool {
+        (self.halfmoves() >= 100 && !self.is_checkmate())
+            || self.position.is_insufficient_material()
+            || self.is_stalemate()
+    }
+
+    /// Every legal move for the side to move, with each pawn promotion
+    /// expanded into one move per promotion piece (Q/R/B/N).
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.generator().legal_moves()
+    }
+
+    /// Every legal move in the position, sorted by the index of the from square,
+    /// then the index of the destination square, then the promotion piece, so the
+    /// order is deterministic regardless of the order moves are generated in.
+    pub fn legal_moves_sorted(&self) -> Vec<Move> {
+        let mut moves = self.legal_moves();
+        moves.sort_unstable_by_key(|(from, dest, promotion)| {
+            (*from as u8, *dest as u8, promotion.map(Piece::index))
+        });
+        moves
+    }
+
+    /// Every legal move that leaves the opponent stalemated, that is, not
+    /// in check but without any legal moves. Useful for finding stalemate
+    /// traps and avoiding accidental stalemates in winning endgames.
+    pub fn moves_causing_stalemate(&self) -> Vec<Move> {
+        self.legal_moves()
+            .into_iter()
+            .filter(|(from, dest, promotion)| {
+                self.play_unchecked(*from, *dest, *promotion).is_stalemate()
+            })
+            .collect()
+    }
+
+    /// If the move is a castle for the side to move, the (from, to) squares
+    /// of the rook, so the rook can be animated alongside the king.
+    /// Returns None for any move that is not a castle.
+    pub fn castle_rook_move(&self, from: Square, dest: Square) -> Option<(Square, Square)> {
+        self.castle_dir(from, dest).map(|dir| {
+            (
+                self.castle.rook_square(self.turn, dir),
+                self.castle.target_squares(self.turn, dir).1,
+            )
+        })
+    }
+
+    /// The direction of the castle, if the move is a castle for the side to move.
+    fn castle_dir(&self, from: Square, dest: Square) -> Option<CastleDir> {
+        if self.position.piece_at(from) != Some((self.turn, Piece::King)) {
+            return None;
+        }
+
+        // the move is a castle if the king is dropped on the
+        // castle target square or the rook square.
+        [CastleDir::Short, CastleDir::Long].into_iter().find(|dir| {
+            self.castle.has_castle(self.turn, self.fullmoves, *dir)
+                && self.castle.castle_play_mask(self.turn, *dir).has(dest)
+        })
+    }
+
+    /// Classify the move, assuming that the move is valid.
+    pub fn classify(&self, from: Square, dest: Square, promote: Option<Piece>) -> MoveKind {
+        let Some((_, piece)) = self.position.piece_at(from) else {
+            return MoveKind::Quiet;
+        };
+
+        // the en passant square is always empty, so it is handled separately.
+        let capture = self.position.color_mask(!self.turn).has(dest);
+
+        match piece {
+            Piece::Pawn => {
+                if promote.is_some() || dest.rank() == (!self.turn).back_rank() {
+                    return if capture {
+                        MoveKind::CapturePromotion
+                    } else {
+                        MoveKind::Promotion
+                    };
+                }
+
+                if self.position.en_passant() == Some(dest) && from.file() != dest.file() {
+                    return MoveKind::EnPassant;
+                }
+
+                if from.rank_distance(dest) == 2 {
+                    return MoveKind::DoublePush;
+                }
+            }
+            Piece::King => {
+                if let Some(dir) = self.castle_dir(from, dest) {
+                    return match dir {
+                        CastleDir::Short => MoveKind::CastleShort,
+                        CastleDir::Long => MoveKind::CastleLong,
+                    };
+                }
+            }
+            _ => {}
+        }
+
+        if capture {
+            MoveKind::Capture
+        } else {
+            MoveKind::Quiet
+        }
+    }
+
     /// Check if a move would require promotion, that is, if a pawn moves to the enemy back rank.
     pub fn move_requires_promotion(&self, from: Square, dest: Square) -> bool {
         if let Some((_, piece)) = self.position.piece_at(from) {
@@ -84,10 +218,40 @@ impl BoardState {
         false
     }
 
+    /// Play a move, after checking it is legal for the side to move according to
+    /// the MoveGenerator, and that a promotion is given if, and only if, the pawn
+    /// reaches the back rank, and that the promotion is to a piece a pawn can become.
+    pub fn play(
+        &self,
+        from: Square,
+        dest: Square,
+        promote: Option<Piece>,
+    ) -> Result<BoardState, MoveError> {
+        match self.position.color_of(from) {
+            None => return Err(MoveError::NoPiece),
+            Some(color) if color != self.turn => return Err(MoveError::WrongTurn),
+            _ => {}
+        }
+
+        if !self.generator().generate(from).has(dest) {
+            return Err(MoveError::IllegalMove);
+        }
+
+        match (self.move_requires_promotion(from, dest), promote) {
+            (true, None) => return Err(MoveError::MissingPromotion),
+            (false, Some(_)) => return Err(MoveError::UnexpectedPromotion),
+            (true, Some(piece)) if !piece.is_promotable() => return Err(MoveError::BadPromotion),
+            _ => {}
+        }
+
+        Ok(self.play_unchecked(from, dest, promote))
+    }
+
     /// Play a move, assuming that it has been validated by a MoveGenerator.
     pub fn play_unchecked(&self, from: Square, dest: Square, promote: Option<Piece>) -> BoardState {
         let mut result = self.position.clone();
         let mut castle = self.castle.clone();
+        let ply = CastleRights::ply(self.fullmoves, self.turn);
 
         // reset the en passant state.
         *result.en_passant_mut() = None;
@@ -100,9 +264,6 @@ impl BoardState {
             match piece {
                 // special case for en passant, promotion, and double pawn pushes.
                 Piece::Pawn => {
-                    // all pawn moves reset the halfmoves.
-                    *result.halfmoves_mut() = 0;
-
                     // if this is a capture en-passant, then remove the en passant'd pawn from the position.
                     if let Some(en_passant_sq) = self.position.en_passant() {
                         if en_passant_sq == dest {
@@ -130,14 +291,9 @@ impl BoardState {
                 Piece::King => {
                     let mut castled = false;
 
-                    // !TODO! - Should this be fullmoves or fullmoves+1?
-                    // all king moves lose castle rights in both directions.
                     for dir in [CastleDir::Short, CastleDir::Long] {
-                        // all king moves lose castling, in both directions.
-                        castle.lose(self.turn, dir, self.fullmoves);
-
                         // if you have not lost castle in this direction,
-                        if castle.has_castle(self.turn, self.fullmoves, dir) {
+                        if self.castle.has_castle(self.turn, self.fullmoves, dir) {
                             // if the destination square is one of the squares identified
                             // as part of the squares that request castling in this direction,
                             // then the move is a castle request.
@@ -158,29 +314,20 @@ impl BoardState {
                                 // so we can avoid updating the king position
                                 // unecessarily.
                                 castled = true;
+                                break;
                             }
                         }
                     }
 
+                    // all king moves lose castle rights in both directions.
+                    for dir in [CastleDir::Short, CastleDir::Long] {
+                        castle.lose(self.turn, dir, ply);
+                    }
+
                     // Set the king to its target square, but not if
                     // castling occured, which would be problematic.
-                    // also increment the halfmoves if the move
-                    // was not a capture.
                     if !castled {
-                        if result.set(dest, Piece::King, self.turn).is_some() {
-                            // if it is not castling, and there is a piece on
-                            // the destination square, then the move is a capture
-                            // and halfmoves can be reset.
-                            *result.halfmoves_mut() = 0;
-                        } else {
-                            // if it is not castling, and there is no piece on
-                            // the destination square, then the move is not a
-                            // capture and halfmoves must be incremented.
-                            *result.halfmoves_mut() += 1;
-                        }
-                    } else {
-                        // castling increments the halfmoves.
-                        *result.halfmoves_mut() += 1;
+                        result.set(dest, Piece::King, self.turn);
                     }
                 }
                 _ => {
@@ -192,22 +339,35 @@ impl BoardState {
                                 // if the rook is moving off of the rook home square in this
                                 // direction, the move forfeits castle in that direction.
                                 if from == self.castle.rook_square(self.turn, dir) {
-                                    castle.lose(self.turn, dir, self.fullmoves);
+                                    castle.lose(self.turn, dir, ply);
                                     break;
                                 }
                             }
                         }
                     }
 
-                    if result.set(dest, piece, self.turn).is_some() {
-                        // if this is a capture, reset the halfmoves.
-                        *result.halfmoves_mut() = 0;
-                    } else {
-                        // if this is not a capture, increment the halfmoves.
-                        *result.halfmoves_mut() += 1;
-                    }
+                    result.set(dest, piece, self.turn);
                 }
             }
+
+            // pawn moves and captures reset the halfmoves, every other
+            // move, castling included, increments them exactly once. The
+            // clock saturates, since it keeps counting past the 75 move rule.
+            if piece == Piece::Pawn || self.position.color_mask(!self.turn).has(dest) {
+                *result.halfmoves_mut() = 0;
+            } else {
+                *result.halfmoves_mut() = result.halfmoves().saturating_add(1);
+            }
+        }
+
+        // capturing a rook on its home square takes away
+        // the opponents' right to castle with that rook.
+        for dir in [CastleDir::Short, CastleDir::Long] {
+            if dest == self.castle.rook_square(!self.turn, dir)
+                && self.castle.has_castle(!self.turn, self.fullmoves, dir)
+            {
+                castle.lose(!self.turn, dir, ply);
+            }
         }
 
         // fullmoves increment when black moves.
@@ -216,11 +376,18 @@ impl BoardState {
             Color::Black => self.fullmoves + 1,
         };
 
+        let hash = self.hash
+            ^ self.position.zobrist_delta(&result)
+            ^ zobrist_castle(&self.castle)
+            ^ zobrist_castle(&castle)
+            ^ cached::ZOBRIST_BLACK_TO_MOVE;
+
         Self {
             position: result,
             castle,
             fullmoves,
             turn: !self.turn,
+            hash,
         }
     }
 
@@ -228,6 +395,9 @@ impl BoardState {
     pub fn notation(&self, from: Square, dest: Square, promote: Option<Piece>) -> MoveString {
         MoveString::from(
             &if let Some((color, piece)) = self.position.piece_at(from) {
+                // SAN uses the uppercase piece ids for both colors.
+                let id = piece.id(Color::White);
+
                 match piece {
                     Piece::Pawn => {
                         // if the files aren't the same, this is a capture.
@@ -242,7 +412,7 @@ impl BoardState {
                                 dest.to_string_lower(),
                                 // promotions are included as '=' + the id of the piece.
                                 if let Some(promotion) = promote {
-                                    format!("={}", promotion.id(color))
+                                    format!("={}", promotion.id(Color::White))
                                 } else {
                                     String::new()
                                 }
@@ -254,7 +424,7 @@ impl BoardState {
                                 dest.to_string_lower(),
                                 // if its a promotion, add '=' + the id of the piece.
                                 if let Some(promotion) = promote {
-                                    format!("={}", promotion.id(color))
+                                    format!("={}", promotion.id(Color::White))
                                 } else {
                                     String::new()
                                 }
@@ -268,18 +438,10 @@ impl BoardState {
                                 // the move is castle in the direction if the king
                                 // is moving to a castle destination square.
                                 if self.castle.castle_play_mask(color, dir).has(dest) {
-                                    let o = if color.is_white() { 'O' } else { 'o' };
-
-                                    return MoveString::from(&format!(
-                                        "{}-{}{}",
-                                        o,
-                                        o,
-                                        if let CastleDir::Long = dir {
-                                            format!("-{}", o)
-                                        } else {
-                                            String::new()
-                                        }
-                                    ))
+                                    return MoveString::from(match dir {
+                                        CastleDir::Long => "O-O-O",
+                                        CastleDir::Short => "O-O",
+                                    })
                                     .unwrap_or_default();
                                 }
                             }
@@ -290,9 +452,9 @@ impl BoardState {
                         // include a prefix since there is only ever one
                         // king on the board of each color.
                         if self.position.piece_at(dest).is_some() {
-                            format!("{}x{}", piece.id(color), dest.to_string_lower())
+                            format!("{}x{}", id, dest.to_string_lower())
                         } else {
-                            format!("{}{}", piece.id(color), dest.to_string_lower())
+                            format!("{}{}", id, dest.to_string_lower())
                         }
                     }
                     _ => {
@@ -327,10 +489,11 @@ impl BoardState {
                         }
 
                         // put it all together, including an 'x' if the move is a capture.
+                        // the prefix goes between the piece id and the destination, as in 'Nbd2'.
                         if self.position.piece_at(dest).is_some() {
-                            format!("{}{}x{}", prefix, piece.id(color), dest.to_string_lower())
+                            format!("{}{}x{}", id, prefix, dest.to_string_lower())
                         } else {
-                            format!("{}{}{}", prefix, piece.id(color), dest.to_string_lower())
+                            format!("{}{}{}", id, prefix, dest.to_string_lower())
                         }
                     }
                 }
@@ -341,6 +504,187 @@ impl BoardState {
         .unwrap_or_default()
     }
 
+    /// Get the notation of the move, as 'notation', with '#' appended if
+    /// the move delivers checkmate or '+' if it delivers check.
+    pub fn notation_annotated(
+        &self,
+        from: Square,
+        dest: Square,
+        promote: Option<Piece>,
+    ) -> MoveString {
+        let mut notation = self.notation(from, dest, promote);
+        let generator = self.play_unchecked(from, dest, promote).generator();
+
+        if generator.is_check() {
+            let suffix = if generator.has_any_moves() { '+' } else { '#' };
+            // MoveString always has room for the suffix after the longest SAN.
+            notation.push(suffix);
+        }
+
+        notation
+    }
+
+    /// Parse a move in Standard Algebraic Notation, for example 'Nf3', 'exd5', 'Nbd2'
+    /// or 'e8=Q', into the move it describes, using the move generator to find the
+    /// piece making the move. Trailing check, mate, and annotation symbols ('+', '#',
+    /// '!', '?') are ignored, even if they don't match the actual position.
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanParseError> {
+        let san = san.trim_end_matches(['+', '#', '!', '?']);
+        let generator = self.generator();
+
+        // castling is the only notation that doesn't end with the destination square.
+        let castle = match san {
+            "O-O" | "o-o" | "0-0" => Some(CastleDir::Short),
+            "O-O-O" | "o-o-o" | "0-0-0" => Some(CastleDir::Long),
+            _ => None,
+        };
+
+        if let Some(dir) = castle {
+            let king = (self.position.kings() & self.position.color_mask(self.turn))
+                .first()
+                .ok_or(SanParseError::IllegalMove)?;
+
+            // castle is requested by dropping the king on its target square or the rook.
+            if self.castle.has_castle(self.turn, self.fullmoves, dir)
+                && generator
+                    .generate(king)
+                    .intersects(self.castle.castle_play_mask(self.turn, dir))
+            {
+                return Ok((king, self.castle.target_squares(self.turn, dir).0, None));
+            } else {
+                return Err(SanParseError::IllegalMove);
+            }
+        }
+
+        // split off the promotion, if there is one, like the '=Q' in 'e8=Q'.
+        let (san, promotion) = match san.split_once('=') {
+            Some((san, id)) => {
+                let mut chars = id.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(id), None) => match piece_from_san_id(id)? {
+                        piece if piece.is_promotable() => (san, Some(piece)),
+                        _ => return Err(SanParseError::BadPromotion),
+                    },
+                    _ => return Err(SanParseError::BadFormat),
+                }
+            }
+            None => (san, None),
+        };
+
+        // pieces are prefixed with their uppercase id, pawns have no prefix.
+        let (piece, san) = match san.chars().next() {
+            Some(id) if id.is_ascii_uppercase() => (piece_from_san_id(id)?, &san[1..]),
+            _ => (Piece::Pawn, san),
+        };
+
+        // the destination square is always the last two characters.
+        let split = san.len().checked_sub(2).ok_or(SanParseError::BadFormat)?;
+        let dest = san
+            .get(split..)
+            .and_then(Square::try_from_string)
+            .ok_or(SanParseError::BadFormat)?;
+
+        // anything between the piece and the destination is
+        // either a capture or information to distinguish the piece.
+        let mut candidates =
+            self.position.masks()[piece.mask_index()] & self.position.color_mask(self.turn);
+
+        for c in san.get(..split).ok_or(SanParseError::BadFormat)?.chars() {
+            if c == 'x' {
+                continue;
+            } else if let Some(file) = File::from_char(c) {
+                candidates &= Bitmask::EMPTY.with_file(file);
+            } else if let Some(rank) = Rank::from_char(c) {
+                candidates &= Bitmask::EMPTY.with_rank(rank);
+            } else {
+                return Err(SanParseError::BadFormat);
+            }
+        }
+
+        // the pieces that match the notation and can legally move to the destination.
+        let movable = candidates
+            .into_iter()
+            .filter(|square| generator.generate(*square).has(dest))
+            .collect::<Bitmask>();
+
+        let from = match movable.count() {
+            0 => return Err(SanParseError::IllegalMove),
+            1 => movable.first().unwrap(),
+            _ => return Err(SanParseError::AmbiguousMove),
+        };
+
+        // promotion must be provided if, and only if, the move requires it.
+        if self.move_requires_promotion(from, dest) != promotion.is_some() {
+            return Err(SanParseError::BadPromotion);
+        }
+
+        Ok((from, dest, promotion))
+    }
+
+    /// Parse a move in UCI long algebraic notation, for example 'e2e4' or 'e7e8q',
+    /// into the move it describes. Castling is written as the king moving to its
+    /// target square, like 'e1g1', or, for Chess960, as the king moving onto the rook.
+    pub fn parse_uci(&self, uci: &str) -> Result<Move, UciParseError> {
+        if !(uci.len() == 4 || uci.len() == 5) || !uci.is_ascii() {
+            return Err(UciParseError::BadFormat);
+        }
+
+        let from = Square::try_from_string(&uci[0..2]).ok_or(UciParseError::BadFormat)?;
+        let dest = Square::try_from_string(&uci[2..4]).ok_or(UciParseError::BadFormat)?;
+
+        let promotion = match uci[4..].chars().next() {
+            Some(id) => match Piece::from_id(id).ok_or(UciParseError::BadFormat)? {
+                piece if piece.is_promotable() => Some(piece),
+                _ => return Err(UciParseError::BadPromotion),
+            },
+            None => None,
+        };
+
+        if self.position.color_of(from) != Some(self.turn)
+            || !self.generator().generate(from).has(dest)
+        {
+            return Err(UciParseError::IllegalMove);
+        }
+
+        // promotion must be provided if, and only if, the move requires it.
+        if self.move_requires_promotion(from, dest) != promotion.is_some() {
+            return Err(UciParseError::BadPromotion);
+        }
+
+        Ok((from, dest, promotion))
+    }
+
+    /// Format a move in UCI long algebraic notation, for example 'e2e4' or 'e7e8q'.
+    /// Castling is written as the king moving to its target square, unless the
+    /// rooks start on non-standard files, in which case the king moves onto the rook.
+    pub fn to_uci(&self, from: Square, dest: Square, promote: Option<Piece>) -> String {
+        let mut dest = dest;
+
+        if let Some((rook, _)) = self.castle_rook_move(from, dest) {
+            let standard = self.castle.kingside_rook_square(self.turn).file() == File::H
+                && self.castle.queenside_rook_square(self.turn).file() == File::A;
+
+            for dir in [CastleDir::Short, CastleDir::Long] {
+                if self.castle.rook_square(self.turn, dir) == rook {
+                    dest = if standard {
+                        self.castle.target_squares(self.turn, dir).0
+                    } else {
+                        rook
+                    };
+                }
+            }
+        }
+
+        let mut result = format!("{}{}", from.to_string_lower(), dest.to_string_lower());
+
+        if let Some(promotion) = promote {
+            result.push(promotion.id(Color::Black));
+        }
+
+        result
+    }
+
     /// Parse a FEN into a BoardState.
     pub fn from_fen(fen: &str) -> Result<Self, FenParseError> {
         let parser = FenParser::parse(fen)?;
@@ -363,12 +707,40 @@ impl BoardState {
             parser.castle()?
         };
 
-        Ok(Self {
+        // castling can only be granted if the king and rook are on their home squares.
+        for color in [Color::White, Color::Black] {
+            let back_rank = Bitmask::EMPTY.with_rank(color.back_rank());
+            let king = (position.kings() & position.color_mask(color) & back_rank).first();
+            let rooks = position.rooks() & position.color_mask(color);
+
+            for dir in [CastleDir::Short, CastleDir::Long] {
+                if castle.has_lost(color, dir) {
+                    continue;
+                }
+
+                let rook = castle.rook_square(color, dir);
+                let Some(king) = king else {
+                    return Err(FenParseError::IllegalCastleRights);
+                };
+
+                // the kingside rook must be right of the king, the queenside rook left of it.
+                let rook_side = match dir {
+                    CastleDir::Short => rook.file() > king.file(),
+                    CastleDir::Long => rook.file() < king.file(),
+                };
+
+                if !rooks.has(rook) || !rook_side {
+                    return Err(FenParseError::IllegalCastleRights);
+                }
+            }
+        }
+
+        Ok(Self::new(
             position,
+            parser.fullmoves()?,
+            parser.turn()?,
             castle,
-            fullmoves: parser.fullmoves()?,
-            turn: parser.turn()?,
-        })
+        ))
     }
 
     /// Serialize the board state to a fen.
@@ -388,17 +760,149 @@ impl BoardState {
     }
 }
 
+impl std::str::FromStr for BoardState {
+    type Err = FenParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_fen(s)
+    }
+}
+
+impl std::fmt::Display for BoardState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_fen())
+    }
+}
+
 impl Default for BoardState {
     fn default() -> Self {
-        Self {
-            position: Position::default(),
-            castle: CastleRights::default(),
-            fullmoves: 1,
-            turn: Color::White,
-        }
+        Self::new(
+            Position::default(),
+            1,
+            Color::White,
+            CastleRights::default(),
+        )
+    }
+}
+
+/// Convert the uppercase id of a piece in SAN to a piece, excluding pawns,
+/// which are never written with an id.
+fn piece_from_san_id(id: char) -> Result<Piece, SanParseError> {
+    match Piece::from_id(id) {
+        Some(Piece::Pawn) | None => Err(SanParseError::BadFormat),
+        Some(piece) if id.is_ascii_uppercase() => Ok(piece),
+        _ => Err(SanParseError::BadFormat),
+    }
+}
+
+/// The reasons 'BoardState::parse_san' can reject a move.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SanParseError {
+    /// The string is not in standard algebraic notation.
+    BadFormat,
+    /// No piece of the side to move can play the move.
+    IllegalMove,
+    /// More than one piece can play the move, and it isn't disambiguated.
+    AmbiguousMove,
+    /// The promotion piece is missing, unexpected, or not promotable.
+    BadPromotion,
+}
+
+impl std::fmt::Display for SanParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::BadFormat => "move is not in standard algebraic notation",
+            Self::IllegalMove => "move is not legal in the position",
+            Self::AmbiguousMove => "more than one piece can make the move",
+            Self::BadPromotion => "promotion piece is missing, unexpected or not promotable",
+        };
+
+        write!(f, "{message}")
+    }
+}
+
+impl std::error::Error for SanParseError {}
+
+/// The reasons 'BoardState::play' can reject a move.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MoveError {
+    /// There is no piece on the from square.
+    NoPiece,
+    /// The piece on the from square belongs to the side not to move.
+    WrongTurn,
+    /// The piece can't legally move to the destination.
+    IllegalMove,
+    /// A pawn reached the back rank without a promotion piece.
+    MissingPromotion,
+    /// A promotion piece was given for a move that isn't a promotion.
+    UnexpectedPromotion,
+    /// The promotion piece is a pawn or a king.
+    BadPromotion,
+}
+
+impl std::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::NoPiece => "there is no piece on the from square",
+            Self::WrongTurn => "the piece belongs to the side not to move",
+            Self::IllegalMove => "the piece can't legally move to the destination",
+            Self::MissingPromotion => "a pawn on the back rank needs a promotion piece",
+            Self::UnexpectedPromotion => "promotion piece given for a move that isn't a promotion",
+            Self::BadPromotion => "pawns can't promote to a pawn or a king",
+        };
+
+        write!(f, "{message}")
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// The kind of a move, as returned by 'BoardState::classify'.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    /// A move to an empty square.
+    Quiet,
+    /// A move that captures the piece on the destination square.
+    Capture,
+    /// A pawn capturing en passant.
+    EnPassant,
+    /// A pawn moving two squares from its starting rank.
+    DoublePush,
+    /// Castling kingside.
+    CastleShort,
+    /// Castling queenside.
+    CastleLong,
+    /// A pawn promoting without a capture.
+    Promotion,
+    /// A pawn capturing onto the back rank and promoting.
+    CapturePromotion,
+}
+
+/// The reasons 'BoardState::parse_uci' can reject a move.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum UciParseError {
+    /// The string is not two squares followed by an optional promotion piece.
+    BadFormat,
+    /// The side to move has no piece that can move between the squares.
+    IllegalMove,
+    /// The promotion piece is missing, unexpected, or not promotable.
+    BadPromotion,
+}
+
+impl std::fmt::Display for UciParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::BadFormat => "move is not in uci long algebraic notation",
+            Self::IllegalMove => "move is not legal in the position",
+            Self::BadPromotion => "promotion piece is missing, unexpected or not promotable",
+        };
+
+        write!(f, "{message}")
     }
 }
 
+impl std::error::Error for UciParseError {}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -442,6 +946,125 @@ mod test {
         );
     }
 
+    #[test]
+    fn from_fen_illegal_castle_rights() {
+        // there is no rook on h1 to castle kingside with.
+        assert!(matches!(
+            BoardState::from_fen("4k3/8/8/8/8/8/8/R3K3 w K - 0 1"),
+            Err(FenParseError::IllegalCastleRights)
+        ));
+        // the king has left its home rank.
+        assert!(matches!(
+            BoardState::from_fen("4k3/8/8/8/8/8/4K3/R6R w KQ - 0 1"),
+            Err(FenParseError::IllegalCastleRights)
+        ));
+        assert!(BoardState::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").is_ok());
+        assert!(BoardState::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").is_ok());
+    }
+
+    #[test]
+    fn from_str_display_round_trip() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2",
+            "r3k2r/8/8/8/8/8/8/4K2R b Kq - 12 40",
+            "4k3/8/8/8/8/8/8/4K3 w - - 99 120",
+        ] {
+            let board = fen.parse::<BoardState>().unwrap();
+
+            assert_eq!(board.to_string(), fen);
+            assert_eq!(board.to_string().parse::<BoardState>().unwrap(), board);
+        }
+    }
+
+    #[test]
+    fn notation_annotated_check() {
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board
+                .notation_annotated(Square::A1, Square::A8, None)
+                .to_string(),
+            "Ra8+"
+        );
+        assert_eq!(
+            board
+                .notation_annotated(Square::A1, Square::A7, None)
+                .to_string(),
+            "Ra7"
+        );
+    }
+
+    #[test]
+    fn notation_annotated_mate() {
+        let board = BoardState::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board
+                .notation_annotated(Square::A1, Square::A8, None)
+                .to_string(),
+            "Ra8#"
+        );
+    }
+
+    #[test]
+    fn castle_rook_move() {
+        let board = BoardState::from_fen(
+            "r2qkb1r/pbp1p3/1pnp1n2/1B3pBp/2PP4/2N1PN2/PP2QPPP/R3K2R w KQkq - 0 1",
+        )
+        .unwrap();
+
+        assert_eq!(
+            board.castle_rook_move(Square::E1, Square::G1),
+            Some((Square::H1, Square::F1))
+        );
+        assert_eq!(
+            board.castle_rook_move(Square::E1, Square::A1),
+            Some((Square::A1, Square::D1))
+        );
+        assert_eq!(board.castle_rook_move(Square::E1, Square::F1), None);
+        assert_eq!(board.castle_rook_move(Square::E2, Square::E3), None);
+    }
+
+    #[test]
+    fn castle_rook_move_960() {
+        let board = BoardState::from_fen("1r2k1r1/8/8/8/8/8/8/1R2K1R1 w GBgb - 0 1").unwrap();
+
+        // the king is dropped on the rook to castle kingside, since its target is the rook square.
+        assert_eq!(
+            board.castle_rook_move(Square::E1, Square::G1),
+            Some((Square::G1, Square::F1))
+        );
+        assert_eq!(
+            board.castle_rook_move(Square::E1, Square::B1),
+            Some((Square::B1, Square::D1))
+        );
+        assert_eq!(
+            board.castle_rook_move(Square::E1, Square::C1),
+            Some((Square::B1, Square::D1))
+        );
+        assert_eq!(board.castle_rook_move(Square::E1, Square::D1), None);
+    }
+
+    #[test]
+    fn play_castle() {
+        let board = BoardState::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        let board = board.play_unchecked(Square::E1, Square::G1, None);
+        assert_eq!(board.to_fen(), "r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1");
+
+        let board = board.play_unchecked(Square::E8, Square::C8, None);
+        assert_eq!(board.to_fen(), "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2");
+    }
+
+    #[test]
+    fn play_captures_castle_rook() {
+        let board = BoardState::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        let board = board.play_unchecked(Square::H1, Square::H8, None);
+        assert_eq!(board.to_fen(), "r3k2R/8/8/8/8/8/8/R3K3 b Qq - 0 1");
+    }
+
     #[test]
     fn notation_short_castle_rook_request() {
         let board = BoardState::from_fen(
@@ -493,4 +1116,518 @@ mod test {
             "dxe6".to_string()
         )
     }
+
+    #[test]
+    fn notation_black() {
+        // black uses the same uppercase ids and castle notation as white.
+        let board = BoardState::from_fen("r3k2r/8/8/8/8/8/8/4K3 b kq - 0 1").unwrap();
+
+        assert_eq!(board.notation(Square::A8, Square::A1, None).as_str(), "Ra1");
+        assert_eq!(board.notation(Square::E8, Square::G8, None).as_str(), "O-O");
+        assert_eq!(
+            board.notation(Square::E8, Square::C8, None).as_str(),
+            "O-O-O"
+        );
+    }
+
+    #[test]
+    fn notation_disambiguation() {
+        // both knights can reach d2, so the file goes after the piece id.
+        let board = BoardState::from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.notation(Square::B1, Square::D2, None).as_str(),
+            "Nbd2"
+        );
+
+        // both rooks are on the a-file, so the rank is used instead.
+        let board = BoardState::from_fen("R7/7k/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.notation(Square::A1, Square::A4, None).as_str(),
+            "R1a4"
+        );
+    }
+
+    #[test]
+    fn parse_san_piece() {
+        let board = BoardState::default();
+
+        assert_eq!(board.parse_san("Nf3"), Ok((Square::G1, Square::F3, None)));
+        assert_eq!(board.parse_san("e4"), Ok((Square::E2, Square::E4, None)));
+        assert_eq!(board.parse_san("Nd4"), Err(SanParseError::IllegalMove));
+        assert_eq!(board.parse_san("Kf"), Err(SanParseError::BadFormat));
+    }
+
+    #[test]
+    fn parse_san_disambiguation() {
+        let board =
+            BoardState::from_fen("rnbqkbnr/pppppppp/8/8/3P4/5N2/PPP1PPPP/RNBQKB1R w KQkq - 0 1")
+                .unwrap();
+
+        assert_eq!(board.parse_san("Nbd2"), Ok((Square::B1, Square::D2, None)));
+        assert_eq!(board.parse_san("Nfd2"), Ok((Square::F3, Square::D2, None)));
+        assert_eq!(board.parse_san("Nf3d2"), Ok((Square::F3, Square::D2, None)));
+        assert_eq!(board.parse_san("Nd2"), Err(SanParseError::AmbiguousMove));
+    }
+
+    #[test]
+    fn parse_san_capture() {
+        let board =
+            BoardState::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2")
+                .unwrap();
+
+        assert_eq!(board.parse_san("exd5"), Ok((Square::E4, Square::D5, None)));
+    }
+
+    #[test]
+    fn parse_san_castle() {
+        let board = BoardState::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        assert_eq!(board.parse_san("O-O"), Ok((Square::E1, Square::G1, None)));
+        assert_eq!(board.parse_san("O-O-O"), Ok((Square::E1, Square::C1, None)));
+        assert_eq!(board.parse_san("0-0+"), Ok((Square::E1, Square::G1, None)));
+
+        let board = BoardState::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1").unwrap();
+        assert_eq!(board.parse_san("O-O"), Err(SanParseError::IllegalMove));
+    }
+
+    #[test]
+    fn parse_san_promotion() {
+        let board = BoardState::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.parse_san("e8=Q+"),
+            Ok((Square::E7, Square::E8, Some(Piece::Queen)))
+        );
+        assert_eq!(
+            board.parse_san("e8=N"),
+            Ok((Square::E7, Square::E8, Some(Piece::Knight)))
+        );
+        assert_eq!(board.parse_san("e8"), Err(SanParseError::BadPromotion));
+        assert_eq!(board.parse_san("e8=K"), Err(SanParseError::BadPromotion));
+        assert_eq!(board.parse_san("e8=X"), Err(SanParseError::BadFormat));
+    }
+
+    #[test]
+    fn play() {
+        let board = BoardState::default();
+
+        let played = board.play(Square::E2, Square::E4, None).unwrap();
+        assert_eq!(played, board.play_unchecked(Square::E2, Square::E4, None));
+
+        assert_eq!(
+            board.play(Square::E2, Square::E5, None),
+            Err(MoveError::IllegalMove)
+        );
+        assert_eq!(
+            board.play(Square::E4, Square::E5, None),
+            Err(MoveError::NoPiece)
+        );
+        assert_eq!(
+            board.play(Square::E7, Square::E5, None),
+            Err(MoveError::WrongTurn)
+        );
+        assert_eq!(
+            board.play(Square::E2, Square::E4, Some(Piece::Queen)),
+            Err(MoveError::UnexpectedPromotion)
+        );
+    }
+
+    #[test]
+    fn move_error_display() {
+        for error in [
+            MoveError::NoPiece,
+            MoveError::WrongTurn,
+            MoveError::IllegalMove,
+            MoveError::MissingPromotion,
+            MoveError::UnexpectedPromotion,
+            MoveError::BadPromotion,
+        ] {
+            assert!(!error.to_string().is_empty());
+        }
+
+        let error: Box<dyn std::error::Error> = Box::new(MoveError::WrongTurn);
+        assert_eq!(
+            error.to_string(),
+            "the piece belongs to the side not to move"
+        );
+    }
+
+    #[test]
+    fn play_promotion() {
+        let board = BoardState::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.play(Square::E7, Square::E8, None),
+            Err(MoveError::MissingPromotion)
+        );
+        assert_eq!(
+            board.play(Square::E7, Square::E8, Some(Piece::King)),
+            Err(MoveError::BadPromotion)
+        );
+
+        let played = board
+            .play(Square::E7, Square::E8, Some(Piece::Queen))
+            .unwrap();
+        assert_eq!(
+            played.position().piece_at(Square::E8),
+            Some((Color::White, Piece::Queen))
+        );
+    }
+
+    #[test]
+    fn parse_uci() {
+        let board = BoardState::default();
+
+        assert_eq!(board.parse_uci("e2e4"), Ok((Square::E2, Square::E4, None)));
+        assert_eq!(board.parse_uci("g1f3"), Ok((Square::G1, Square::F3, None)));
+        assert_eq!(board.parse_uci("e2e5"), Err(UciParseError::IllegalMove));
+        assert_eq!(board.parse_uci("e7e5"), Err(UciParseError::IllegalMove));
+        assert_eq!(board.parse_uci("e2"), Err(UciParseError::BadFormat));
+        assert_eq!(board.parse_uci("z2e4"), Err(UciParseError::BadFormat));
+    }
+
+    #[test]
+    fn parse_uci_promotion() {
+        let board = BoardState::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.parse_uci("e7e8q"),
+            Ok((Square::E7, Square::E8, Some(Piece::Queen)))
+        );
+        assert_eq!(
+            board.parse_uci("e7e8n"),
+            Ok((Square::E7, Square::E8, Some(Piece::Knight)))
+        );
+        assert_eq!(board.parse_uci("e7e8"), Err(UciParseError::BadPromotion));
+        assert_eq!(board.parse_uci("e7e8k"), Err(UciParseError::BadPromotion));
+        assert_eq!(
+            board.to_uci(Square::E7, Square::E8, Some(Piece::Queen)),
+            "e7e8q"
+        );
+    }
+
+    #[test]
+    fn uci_parse_error_display() {
+        for error in [
+            UciParseError::BadFormat,
+            UciParseError::IllegalMove,
+            UciParseError::BadPromotion,
+        ] {
+            assert!(!error.to_string().is_empty());
+        }
+
+        let error: Box<dyn std::error::Error> = Box::new(UciParseError::BadFormat);
+        assert_eq!(
+            error.to_string(),
+            "move is not in uci long algebraic notation"
+        );
+    }
+
+    #[test]
+    fn parse_uci_castle() {
+        let board = BoardState::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        assert_eq!(board.parse_uci("e1g1"), Ok((Square::E1, Square::G1, None)));
+        assert_eq!(board.parse_uci("e1h1"), Ok((Square::E1, Square::H1, None)));
+        assert_eq!(board.to_uci(Square::E1, Square::G1, None), "e1g1");
+        assert_eq!(board.to_uci(Square::E1, Square::H1, None), "e1g1");
+        assert_eq!(board.to_uci(Square::E1, Square::A1, None), "e1c1");
+
+        let board = BoardState::from_fen("1r2k1r1/8/8/8/8/8/8/1R2K1R1 w GBgb - 0 1").unwrap();
+
+        assert_eq!(board.parse_uci("e1b1"), Ok((Square::E1, Square::B1, None)));
+        assert_eq!(board.to_uci(Square::E1, Square::C1, None), "e1b1");
+    }
+
+    #[test]
+    fn parse_san_ignores_suffixes() {
+        let board = BoardState::from_fen(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+        )
+        .unwrap();
+
+        let expected = Ok((Square::H5, Square::F7, None));
+        assert_eq!(board.parse_san("Qxf7#"), expected);
+        assert_eq!(board.parse_san("Qxf7+"), expected);
+        assert_eq!(board.parse_san("Qxf7!!"), expected);
+        assert_eq!(board.parse_san("Qxf7"), expected);
+
+        // Nf3 is not check, but the suffix is still ignored.
+        let board = BoardState::default();
+        let expected = Ok((Square::G1, Square::F3, None));
+        assert_eq!(board.parse_san("Nf3+"), expected);
+        assert_eq!(board.parse_san("Nf3#"), expected);
+        assert_eq!(board.parse_san("Nf3!?"), expected);
+    }
+
+    #[test]
+    fn san_parse_error_display() {
+        for error in [
+            SanParseError::BadFormat,
+            SanParseError::IllegalMove,
+            SanParseError::AmbiguousMove,
+            SanParseError::BadPromotion,
+        ] {
+            assert!(!error.to_string().is_empty());
+        }
+
+        let error: Box<dyn std::error::Error> = Box::new(SanParseError::AmbiguousMove);
+        assert_eq!(error.to_string(), "more than one piece can make the move");
+    }
+
+    #[test]
+    fn is_checkmate() {
+        // fool's mate.
+        let board =
+            BoardState::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+                .unwrap();
+
+        assert!(board.is_checkmate());
+        assert!(!board.is_stalemate());
+        assert!(!board.is_draw());
+        assert!(!BoardState::default().is_checkmate());
+    }
+
+    #[test]
+    fn is_stalemate() {
+        let board = BoardState::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+
+        assert!(board.is_stalemate());
+        assert!(!board.is_checkmate());
+        assert!(board.is_draw());
+    }
+
+    #[test]
+    fn is_draw() {
+        assert!(!BoardState::default().is_draw());
+
+        // the 50-move rule.
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 100 80").unwrap();
+        assert!(board.is_draw());
+
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+        assert!(!board.is_draw());
+
+        // the 100th halfmove is checkmate, which is not a draw.
+        let board = BoardState::from_fen("6k1/8/6K1/8/8/8/8/R7 w - - 99 80")
+            .unwrap()
+            .play_unchecked(Square::A1, Square::A8, None);
+        assert_eq!(board.halfmoves(), 100);
+        assert!(board.is_checkmate());
+        assert!(!board.is_draw());
+
+        // king and bishop against king.
+        let board = BoardState::from_fen("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1").unwrap();
+        assert!(board.is_draw());
+    }
+
+    #[test]
+    fn classify() {
+        let board = BoardState::from_fen(
+            "r3k2r/pppq1ppp/2np1n2/2b1pPb1/2B1P3/2NP1N2/PPPQ2PP/R3K2R w KQkq e6 0 9",
+        )
+        .unwrap();
+
+        assert_eq!(
+            board.classify(Square::F5, Square::E6, None),
+            MoveKind::EnPassant
+        );
+        assert_eq!(
+            board.classify(Square::H2, Square::H4, None),
+            MoveKind::DoublePush
+        );
+        assert_eq!(
+            board.classify(Square::H2, Square::H3, None),
+            MoveKind::Quiet
+        );
+        assert_eq!(
+            board.classify(Square::E1, Square::G1, None),
+            MoveKind::CastleShort
+        );
+        assert_eq!(
+            board.classify(Square::E1, Square::H1, None),
+            MoveKind::CastleShort
+        );
+        assert_eq!(
+            board.classify(Square::E1, Square::C1, None),
+            MoveKind::CastleLong
+        );
+        assert_eq!(
+            board.classify(Square::F3, Square::G5, None),
+            MoveKind::Capture
+        );
+        assert_eq!(
+            board.classify(Square::E1, Square::F1, None),
+            MoveKind::Quiet
+        );
+    }
+
+    #[test]
+    fn classify_promotion() {
+        let board = BoardState::from_fen("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.classify(Square::A7, Square::A8, Some(Piece::Queen)),
+            MoveKind::Promotion
+        );
+        assert_eq!(
+            board.classify(Square::A7, Square::B8, Some(Piece::Knight)),
+            MoveKind::CapturePromotion
+        );
+    }
+
+    #[test]
+    fn zobrist_hash_incremental() {
+        let mut state = BoardState::default();
+        assert_eq!(
+            state.zobrist_hash(),
+            state.position().zobrist_hash(state.turn(), &state.castle())
+        );
+
+        // a capture, a double push and a castle for each side.
+        for san in [
+            "e4", "d5", "exd5", "Qxd5", "Nf3", "Bg4", "Be2", "Nc6", "O-O", "O-O-O",
+        ] {
+            let (from, dest, promote) = state.parse_san(san).unwrap();
+            state = state.play_unchecked(from, dest, promote);
+
+            assert_eq!(
+                state.zobrist_hash(),
+                state.position().zobrist_hash(state.turn(), &state.castle()),
+                "hash diverged after {san}"
+            );
+        }
+
+        assert_eq!(
+            state.zobrist_hash(),
+            BoardState::from_fen(&state.to_fen())
+                .unwrap()
+                .zobrist_hash()
+        );
+    }
+
+    #[test]
+    fn zobrist_hash_incremental_special_moves() {
+        // an en passant capture, then a promotion that captures a rook on its home square.
+        let state = BoardState::from_fen("r3k3/1P6/8/8/3pP3/8/8/4K3 b q e3 0 1").unwrap();
+        let state = state.play_unchecked(Square::D4, Square::E3, None);
+        let state = state.play_unchecked(Square::B7, Square::A8, Some(Piece::Queen));
+
+        assert_eq!(
+            state.zobrist_hash(),
+            state.position().zobrist_hash(state.turn(), &state.castle())
+        );
+        assert_eq!(
+            state.zobrist_hash(),
+            BoardState::from_fen(&state.to_fen())
+                .unwrap()
+                .zobrist_hash()
+        );
+    }
+
+    #[test]
+    fn halfmoves() {
+        let board = BoardState::from_fen("r3k3/8/8/8/8/7p/p7/RN2K2R w K - 10 20").unwrap();
+
+        // quiet knight move.
+        assert_eq!(
+            board
+                .play_unchecked(Square::B1, Square::C3, None)
+                .halfmoves(),
+            11
+        );
+        // quiet king move.
+        assert_eq!(
+            board
+                .play_unchecked(Square::E1, Square::D1, None)
+                .halfmoves(),
+            11
+        );
+        // capture.
+        assert_eq!(
+            board
+                .play_unchecked(Square::A1, Square::A2, None)
+                .halfmoves(),
+            0
+        );
+        // castle, dropping the king on either the target square or the rook.
+        assert_eq!(
+            board
+                .play_unchecked(Square::E1, Square::G1, None)
+                .halfmoves(),
+            11
+        );
+        assert_eq!(
+            board
+                .play_unchecked(Square::E1, Square::H1, None)
+                .halfmoves(),
+            11
+        );
+
+        // pawn move.
+        let board = board.play_unchecked(Square::B1, Square::C3, None);
+        assert_eq!(
+            board
+                .play_unchecked(Square::H3, Square::H2, None)
+                .halfmoves(),
+            0
+        );
+
+        // the clock stops at the largest value it can hold.
+        let mut board = BoardState::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 150 200").unwrap();
+        for (from, dest) in [
+            (Square::E1, Square::D1),
+            (Square::E8, Square::D8),
+            (Square::D1, Square::E1),
+            (Square::D8, Square::E8),
+        ]
+        .into_iter()
+        .cycle()
+        .take(120)
+        {
+            board = board.play_unchecked(from, dest, None);
+        }
+        assert_eq!(board.halfmoves(), 255);
+    }
+
+    #[test]
+    fn legal_moves() {
+        assert_eq!(BoardState::default().legal_moves().len(), 20);
+
+        // both pawns can promote to four pieces, plus three king moves.
+        let board = BoardState::from_fen("4k3/P6P/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        let moves = board.legal_moves();
+
+        assert_eq!(moves.len(), 4 + 4 + 3);
+        for piece in Piece::PROMOTIONS {
+            assert!(moves.contains(&(Square::H7, Square::H8, Some(piece))));
+        }
+    }
+
+    #[test]
+    fn legal_moves_sorted() {
+        let moves = BoardState::default().legal_moves_sorted();
+
+        assert_eq!(moves.first(), Some(&(Square::B1, Square::A3, None)));
+        assert_eq!(moves.last(), Some(&(Square::H2, Square::H4, None)));
+    }
+
+    #[test]
+    fn moves_causing_stalemate() {
+        // Qc7 takes away every square from the king, while Qc8 is mate.
+        let board = BoardState::from_fen("k7/8/1K6/2Q5/8/8/8/8 w - - 0 1").unwrap();
+        let moves = board.moves_causing_stalemate();
+
+        assert!(moves.contains(&(Square::C5, Square::C7, None)));
+        assert!(!moves.contains(&(Square::C5, Square::C8, None)));
+        assert_eq!(
+            moves,
+            vec![
+                (Square::B6, Square::C7, None),
+                (Square::C5, Square::E5, None),
+                (Square::C5, Square::D6, None),
+                (Square::C5, Square::C7, None),
+            ]
+        );
+    }
 }
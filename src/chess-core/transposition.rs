@@ -0,0 +1,145 @@
+/// How many buckets past the home bucket of a hash are searched
+/// before an insert gives up and replaces the home bucket.
+const PROBES: usize = 4;
+
+/// A fixed-size hash table for search results, keyed by Zobrist hash.
+/// Buckets are found with open addressing, and the full hash is stored
+/// so that two positions landing in the same bucket are told apart.
+#[derive(Clone, Debug)]
+pub struct TranspositionTable<V> {
+    buckets: Vec<Option<(u64, V)>>,
+    /// The number of buckets in use.
+    len: usize,
+}
+
+impl<V> TranspositionTable<V> {
+    /// Create a table with at least 'capacity' buckets,
+    /// rounded up to the next power of two.
+    pub fn new(capacity: usize) -> Self {
+        let mut buckets = Vec::new();
+        buckets.resize_with(capacity.max(1).next_power_of_two(), || None);
+
+        Self { buckets, len: 0 }
+    }
+
+    /// Get the value stored for the hash.
+    pub fn get(&self, hash: u64) -> Option<&V> {
+        self.probe(hash)
+            .find_map(|index| match &self.buckets[index] {
+                Some((key, value)) if *key == hash => Some(value),
+                _ => None,
+            })
+    }
+
+    /// Store a value for the hash, overwriting the value already stored for
+    /// it. If every bucket it can go in is taken by another hash, the entry
+    /// in its home bucket is replaced, since the newest result is usually
+    /// the most useful.
+    pub fn insert(&mut self, hash: u64, value: V) {
+        let index = self
+            .probe(hash)
+            .find(|index| match &self.buckets[*index] {
+                Some((key, _)) => *key == hash,
+                None => true,
+            })
+            .unwrap_or(self.home(hash));
+
+        if self.buckets[index].is_none() {
+            self.len += 1;
+        }
+
+        self.buckets[index] = Some((hash, value));
+    }
+
+    /// Remove every entry, keeping the buckets allocated.
+    pub fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(|bucket| *bucket = None);
+        self.len = 0;
+    }
+
+    /// The number of entries in the table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the table has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of buckets in the table.
+    pub fn capacity(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// The fraction of buckets in use, from 0.0 to 1.0.
+    pub fn fill_ratio(&self) -> f32 {
+        self.len as f32 / self.buckets.len() as f32
+    }
+
+    /// The bucket a hash is first looked for in.
+    fn home(&self, hash: u64) -> usize {
+        hash as usize & (self.buckets.len() - 1)
+    }
+
+    /// The buckets a hash can be stored in, in the order they are searched.
+    fn probe(&self, hash: u64) -> impl Iterator<Item = usize> {
+        let home = self.home(hash);
+        let mask = self.buckets.len() - 1;
+
+        (0..PROBES.min(self.buckets.len())).map(move |i| (home + i) & mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::BoardState;
+
+    #[test]
+    fn insert_get() {
+        let mut table = TranspositionTable::new(1000);
+        assert_eq!(table.capacity(), 1024);
+        assert!(table.is_empty());
+
+        let state = BoardState::default();
+        table.insert(state.zobrist_hash(), 20);
+        assert_eq!(table.get(state.zobrist_hash()), Some(&20));
+        assert_eq!(table.get(state.zobrist_hash() ^ 1), None);
+
+        // inserting the same hash again overwrites it.
+        table.insert(state.zobrist_hash(), 400);
+        assert_eq!(table.get(state.zobrist_hash()), Some(&400));
+        assert_eq!(table.len(), 1);
+        assert_eq!(table.fill_ratio(), 1.0 / 1024.0);
+
+        table.clear();
+        assert!(table.is_empty());
+        assert_eq!(table.get(state.zobrist_hash()), None);
+        assert_eq!(table.capacity(), 1024);
+    }
+
+    #[test]
+    fn collisions() {
+        let mut table = TranspositionTable::new(16);
+
+        // both hashes have the same home bucket.
+        table.insert(5, 'a');
+        table.insert(5 + 16, 'b');
+        assert_eq!(table.get(5), Some(&'a'));
+        assert_eq!(table.get(5 + 16), Some(&'b'));
+        assert_eq!(table.get(5 + 32), None);
+        assert_eq!(table.len(), 2);
+
+        // once every bucket the hash can go in is taken,
+        // the entry in the home bucket is replaced.
+        table.insert(5 + 32, 'c');
+        table.insert(5 + 48, 'd');
+        table.insert(5 + 64, 'e');
+        assert_eq!(table.get(5), None);
+        assert_eq!(table.get(5 + 64), Some(&'e'));
+        assert_eq!(table.get(5 + 16), Some(&'b'));
+        assert_eq!(table.len(), 4);
+        assert_eq!(table.fill_ratio(), 0.25);
+    }
+}